@@ -1,7 +1,7 @@
 use criterion::{criterion_group, criterion_main, Criterion};
 
 use namadexer::BlockInfo;
-use namadexer::{utils::load_checksums, Database};
+use namadexer::{utils::load_checksums, Database, DecoderRegistry};
 use sqlx::Row;
 use std::convert::TryFrom;
 
@@ -54,8 +54,16 @@ async fn prepare_database() -> Database {
     let mut blocks = utils::load_blocks();
     let results = utils::load_block_results();
     let checksums = load_checksums().unwrap();
-
-    utils::save_blocks(&db, blocks.iter_mut(), results.iter(), &checksums).await;
+    let decoders = DecoderRegistry::default();
+
+    utils::save_blocks(
+        &db,
+        blocks.iter_mut(),
+        results.iter(),
+        &checksums,
+        &decoders,
+    )
+    .await;
 
     db
 }
@@ -1,6 +1,7 @@
 use criterion::{criterion_group, criterion_main, Criterion};
 
 use namadexer::utils::load_checksums;
+use namadexer::DecoderRegistry;
 use tendermint::block::Height;
 
 mod utils;
@@ -34,6 +35,7 @@ fn save_blocks_benchmark(c: &mut Criterion) {
     let mut blocks = utils::load_blocks();
     let results = utils::load_block_results();
     let checksums_map = load_checksums().unwrap();
+    let decoders = DecoderRegistry::default();
 
     // start benchmarking here
     c.bench_function("save_block", |b| {
@@ -47,7 +49,14 @@ fn save_blocks_benchmark(c: &mut Criterion) {
                     b
                 });
 
-                utils::save_blocks(&save_blocks_db, iter, results.iter(), &checksums_map).await
+                utils::save_blocks(
+                    &save_blocks_db,
+                    iter,
+                    results.iter(),
+                    &checksums_map,
+                    &decoders,
+                )
+                .await
             });
         });
     });
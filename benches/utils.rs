@@ -1,7 +1,6 @@
-use namadexer::{Database, Settings};
+use namadexer::{Checksums, Database, DecoderRegistry, Settings};
 use sqlx::query;
 use sqlx::PgPool;
-use std::collections::HashMap;
 use std::fs;
 use tendermint::block::Block;
 use tendermint_rpc::endpoint::block_results;
@@ -61,10 +60,13 @@ pub async fn save_blocks(
     db: &Database,
     blocks: impl Iterator<Item = &mut Block>,
     results: impl Iterator<Item = &block_results::Response>,
-    checksums: &HashMap<String, String>,
+    checksums: &Checksums,
+    decoders: &DecoderRegistry,
 ) {
     for (block, result) in blocks.zip(results) {
-        db.save_block(block, result, checksums).await.unwrap();
+        db.save_block(block, result, checksums, decoders)
+            .await
+            .unwrap();
     }
 }
 
@@ -0,0 +1,134 @@
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde_json::Value;
+use sqlx::Row as TRow;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use tracing::{info, instrument};
+
+use crate::database::Database;
+use crate::error::Error;
+
+// Number of blocks queried at once while exporting.
+const EXPORT_BATCH_SIZE: u32 = 1000;
+
+// First bytes of any gzip file.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Writes the blocks between `from` and `to` (both included), with their
+/// transactions, evidences and commit signatures, to `output` as NDJSON.
+/// The file is gzip compressed if `compress` is set.
+///
+/// `to` defaults to the last height stored.
+#[instrument(skip(db))]
+pub async fn export(
+    db: &Database,
+    from: u32,
+    to: Option<u32>,
+    output: &Path,
+    compress: bool,
+) -> Result<(), Error> {
+    let to = match to {
+        Some(to) => to,
+        None => {
+            let last: Option<i32> = db.get_last_height().await?.try_get("header_height")?;
+            last.unwrap_or_default() as u32
+        }
+    };
+
+    let file = BufWriter::new(File::create(output)?);
+    let mut writer: Box<dyn Write + Send> = if compress {
+        Box::new(GzEncoder::new(file, Compression::default()))
+    } else {
+        Box::new(file)
+    };
+
+    let mut count = 0;
+    let mut start = from;
+
+    while start <= to {
+        let end = to.min(start.saturating_add(EXPORT_BATCH_SIZE - 1));
+
+        for block in db.export_blocks(start, end).await? {
+            writeln!(writer, "{}", block)?;
+            count += 1;
+        }
+
+        info!("Exported blocks {} to {}", start, end);
+
+        if end == u32::MAX {
+            break;
+        }
+        start = end + 1;
+    }
+
+    writer.flush()?;
+    // dropping the encoder writes the gzip footer
+    drop(writer);
+
+    info!("{} blocks exported to {}", count, output.display());
+
+    Ok(())
+}
+
+/// Saves the blocks of a file written by [export], compressed or not.
+///
+/// Blocks already stored are skipped as long as they have the same id, and
+/// every new block must point to the block stored right bellow it if any.
+#[instrument(skip(db))]
+pub async fn import(db: &Database, input: &Path) -> Result<(), Error> {
+    let mut reader = BufReader::new(File::open(input)?);
+
+    let reader: Box<dyn BufRead + Send> = if reader.fill_buf()?.starts_with(&GZIP_MAGIC) {
+        Box::new(BufReader::new(MultiGzDecoder::new(reader)))
+    } else {
+        Box::new(reader)
+    };
+
+    let mut count = 0;
+
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let block: Value = serde_json::from_str(&line)?;
+
+        let height = block["block"]["header_height"]
+            .as_u64()
+            .ok_or(Error::InvalidBlockData)?;
+        let block_id = bytea(&block["block"]["block_id"]).ok_or(Error::InvalidBlockData)?;
+
+        if let Some(stored_id) = db.block_id_by_height(height as u32).await? {
+            if stored_id != block_id {
+                return Err(Error::ChainMismatch(height));
+            }
+            continue;
+        }
+
+        let parent_id = db
+            .block_id_by_height((height as u32).saturating_sub(1))
+            .await?;
+        if let Some(parent_id) = parent_id {
+            if bytea(&block["block"]["header_last_block_id_hash"]) != Some(parent_id) {
+                return Err(Error::ChainMismatch(height));
+            }
+        }
+
+        db.import_block(&block).await?;
+        count += 1;
+    }
+
+    info!("{} blocks imported from {}", count, input.display());
+
+    Ok(())
+}
+
+// Decodes a BYTEA value as serialized by postgres to JSON.
+fn bytea(value: &Value) -> Option<Vec<u8>> {
+    let hex_str = value.as_str()?.strip_prefix("\\x")?;
+    hex::decode(hex_str).ok()
+}
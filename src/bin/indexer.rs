@@ -1,8 +1,18 @@
+use clap::{Parser, Subcommand};
+use namadexer::export;
+use namadexer::genesis;
+use namadexer::import;
+use namadexer::ingest;
+use namadexer::redecode;
+use namadexer::repair;
 use namadexer::setup_logging;
 use namadexer::start_indexing;
+use namadexer::CliSettings;
 use namadexer::Database;
+use namadexer::DecoderRegistry;
 use namadexer::Error;
 
+use std::path::PathBuf;
 use tracing::info;
 
 #[cfg(feature = "prometheus")]
@@ -25,6 +35,82 @@ pub const GET_BLOCK_DURATION_SECONDS_BUCKETS: &[f64; 11] = &[
     0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
 ];
 
+#[derive(Debug, Parser)]
+#[command(author, version, about, long_about = None)]
+struct Cli {
+    #[command(flatten)]
+    settings: CliSettings,
+
+    /// Height to start indexing at when the database is empty.
+    /// Used with --end-height, the lower bound of the range to backfill.
+    #[clap(long, env = "INDEXER_START_HEIGHT")]
+    start_height: Option<u32>,
+
+    /// Backfill the blocks missing between --start-height and this height
+    /// while live indexing resumes above it.
+    #[clap(long, env = "INDEXER_END_HEIGHT")]
+    end_height: Option<u32>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Index the blocks missing between the lowest and the highest
+    /// height stored, then exit.
+    Repair,
+    /// Index blocks from archive files instead of a node, then exit.
+    Ingest {
+        /// File holding the blocks, as a JSON array or one block per line.
+        #[clap(long)]
+        blocks: PathBuf,
+        /// File holding the matching block results, in the same order.
+        #[clap(long)]
+        block_results: PathBuf,
+    },
+    /// Write the indexed blocks of a height range to a NDJSON file, then exit.
+    Export {
+        /// First height to export.
+        #[clap(long, default_value_t = 1)]
+        from: u32,
+        /// Last height to export, the last height indexed if not set.
+        #[clap(long)]
+        to: Option<u32>,
+        /// File to write the blocks to.
+        #[clap(long)]
+        output: PathBuf,
+        /// Compress the file with gzip.
+        #[clap(long)]
+        compress: bool,
+    },
+    /// Save the blocks of a file written by the export command, then exit.
+    Import {
+        /// File to read the blocks from, compressed or not.
+        #[clap(long)]
+        input: PathBuf,
+    },
+    /// Decode again the transactions kept raw by store_raw_txs and
+    /// update their data, then exit.
+    Redecode {
+        /// Only decode the transactions of this type (e.g tx_transfer).
+        #[clap(long = "type")]
+        tx_type: Option<String>,
+        /// First height to decode.
+        #[clap(long, default_value_t = 1)]
+        from: u32,
+        /// Last height to decode, the last height indexed if not set.
+        #[clap(long)]
+        to: Option<u32>,
+    },
+    /// Save the consensus keys of the validators created at genesis, then exit.
+    Genesis {
+        /// JSON file mapping the address of each validator to its consensus key.
+        #[clap(long)]
+        input: PathBuf,
+    },
+}
+
 #[cfg(feature = "prometheus")]
 async fn start_metrics_server(cfg: &PrometheusConfig) -> Result<(), Error> {
     let address = cfg.address()?;
@@ -45,15 +131,42 @@ async fn start_metrics_server(cfg: &PrometheusConfig) -> Result<(), Error> {
 
 #[tokio::main(flavor = "multi_thread", worker_threads = 4)]
 async fn main() -> Result<(), Error> {
-    let cfg = Settings::new()?;
+    let cli = Cli::parse();
+    let mut cfg = Settings::with_cli_settings(cli.settings)?;
+
+    // command line heights take precedence over the configuration file
+    if cli.start_height.is_some() {
+        cfg.indexer.start_height = cli.start_height;
+    }
+    if cli.end_height.is_some() {
+        cfg.indexer.end_height = cli.end_height;
+    }
+
+    if let (Some(start), Some(end)) = (cfg.indexer.start_height, cfg.indexer.end_height) {
+        if start > end {
+            panic!("start_height ({start}) must be lower than end_height ({end})");
+        }
+    }
 
     setup_logging(&cfg);
 
     info!("Starting database connection");
 
     let db = Database::new(cfg.database_config(), cfg.chain_name.as_str()).await?;
+    // decoders of the transaction types shipped with Namada
+    let decoders = DecoderRegistry::default();
+
     info!("Creating tables");
     db.create_tables().await?;
+    db.create_decoder_tables(&decoders).await?;
+
+    let indexer = cfg.indexer_config();
+    db.save_pos_parameters(
+        indexer.pipeline_len,
+        indexer.unbonding_len,
+        indexer.cubic_slashing_window_len,
+    )
+    .await?;
 
     // start metrics service
     #[cfg(feature = "prometheus")]
@@ -61,12 +174,49 @@ async fn main() -> Result<(), Error> {
 
     let network = db.network.clone();
 
+    match cli.command {
+        Some(Command::Repair) => {
+            info!("Repairing missing blocks");
+            return repair(db, cfg.indexer_config(), network.as_str(), &decoders).await;
+        }
+        Some(Command::Ingest {
+            blocks,
+            block_results,
+        }) => {
+            info!("Ingesting blocks from {}", blocks.display());
+            return ingest(db, &blocks, &block_results, &decoders).await;
+        }
+        Some(Command::Export {
+            from,
+            to,
+            output,
+            compress,
+        }) => {
+            info!("Exporting blocks to {}", output.display());
+            return export(&db, from, to, &output, compress).await;
+        }
+        Some(Command::Import { input }) => {
+            info!("Importing blocks from {}", input.display());
+            return import(&db, &input).await;
+        }
+        Some(Command::Redecode { tx_type, from, to }) => {
+            info!("Decoding transactions again");
+            return redecode(db, &decoders, tx_type.as_deref(), from, to).await;
+        }
+        Some(Command::Genesis { input }) => {
+            info!("Saving genesis validators from {}", input.display());
+            return genesis(db, &input).await;
+        }
+        None => {}
+    }
+
     info!("Starting indexer");
     start_indexing(
         db,
         cfg.indexer_config(),
         network.as_str(),
         cfg.database.create_index,
+        &decoders,
     )
     .await
 }
@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+
+/// Range of heights a code hash is known to stand for a transaction type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChecksumRange {
+    pub tx_type: String,
+    pub start_height: u64,
+    // None means the code hash is still in use
+    pub end_height: Option<u64>,
+}
+
+impl ChecksumRange {
+    fn contains(&self, height: u64) -> bool {
+        self.start_height <= height && self.end_height.map_or(true, |end| height <= end)
+    }
+}
+
+/// Registry mapping the wasm code hashes to transaction types.
+///
+/// A governance proposal can upgrade a transaction wasm so a code hash is
+/// only valid for a range of heights, transactions are always resolved
+/// against the height of the block they are part of.
+#[derive(Debug, Clone, Default)]
+pub struct Checksums {
+    ranges: HashMap<String, Vec<ChecksumRange>>,
+}
+
+impl Checksums {
+    /// Registers `code_hash` as `tx_type` for the given range of heights.
+    pub fn insert(&mut self, code_hash: String, range: ChecksumRange) {
+        let ranges = self.ranges.entry(code_hash).or_default();
+        ranges.retain(|r| r.start_height != range.start_height);
+        ranges.push(range);
+    }
+
+    /// Returns the transaction type of the code `code_hash`, hex encoded,
+    /// at `height`.
+    pub fn tx_type(&self, code_hash: &str, height: u64) -> Option<&str> {
+        self.ranges
+            .get(code_hash)?
+            .iter()
+            .find(|r| r.contains(height))
+            .map(|r| r.tx_type.as_str())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &ChecksumRange)> {
+        self.ranges
+            .iter()
+            .flat_map(|(hash, ranges)| ranges.iter().map(move |r| (hash, r)))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.ranges.values().map(Vec::len).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tx_type_depends_on_height() {
+        let mut checksums = Checksums::default();
+        checksums.insert(
+            "aa".to_string(),
+            ChecksumRange {
+                tx_type: "tx_transfer".to_string(),
+                start_height: 0,
+                end_height: Some(100),
+            },
+        );
+        checksums.insert(
+            "bb".to_string(),
+            ChecksumRange {
+                tx_type: "tx_transfer".to_string(),
+                start_height: 101,
+                end_height: None,
+            },
+        );
+
+        assert_eq!(checksums.tx_type("aa", 100), Some("tx_transfer"));
+        assert_eq!(checksums.tx_type("aa", 101), None);
+        assert_eq!(checksums.tx_type("bb", 100), None);
+        assert_eq!(checksums.tx_type("bb", 5000), Some("tx_transfer"));
+        assert_eq!(checksums.len(), 2);
+    }
+}
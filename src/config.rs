@@ -12,6 +12,18 @@ pub const SERVER_PORT: u16 = 30303;
 
 pub const TENDERMINT_ADDR: &str = "http://127.0.0.1:26657";
 
+pub const FETCH_CONCURRENCY: usize = 4;
+pub const BACKOFF_MIN_MS: u64 = 500;
+pub const BACKOFF_MAX_MS: u64 = 30_000;
+pub const RPC_MAX_FAILURES: u32 = 3;
+pub const RPC_MAX_HEIGHT_LAG: u64 = 10;
+pub const CHECKSUMS_RELOAD_INTERVAL: u64 = 300;
+
+// Proof of stake parameters of Namada mainnet, in epochs.
+pub const PIPELINE_LEN: u64 = 2;
+pub const UNBONDING_LEN: u64 = 21;
+pub const CUBIC_SLASHING_WINDOW_LEN: u64 = 1;
+
 pub const JAEGER_HOST: &str = "localhost";
 pub const JAEGER_PORT: u16 = 6831;
 
@@ -22,9 +34,89 @@ pub const DEFAULT_CHAIN_NAME: &str = "public-testnet-15";
 
 pub const DEFAULT_LOG_FORMAT: &str = "pretty";
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Clone)]
 pub struct IndexerConfig {
     pub tendermint_addr: String,
+    // Extra RPC endpoints of the same chain used for failover
+    // or load balancing along with tendermint_addr.
+    #[serde(default)]
+    pub tendermint_addrs: Vec<String>,
+    // Spread requests across all the healthy endpoints instead
+    // of sticking to one of them.
+    #[serde(default)]
+    pub rpc_load_balance: bool,
+    // Consecutive failed requests before an endpoint is considered unhealthy
+    #[serde(default = "default_rpc_max_failures")]
+    pub rpc_max_failures: u32,
+    // Max number of blocks an endpoint can be behind the highest known
+    // tip before it is considered unhealthy
+    #[serde(default = "default_rpc_max_height_lag")]
+    pub rpc_max_height_lag: u64,
+    // Websocket endpoint (e.g ws://127.0.0.1:26657/websocket) used to be
+    // notified about new blocks once synced, polling is used if not set.
+    pub websocket_addr: Option<String>,
+    // Height to start at when the database is empty. If end_height is
+    // also set, the range is backfilled alongside the live indexing.
+    pub start_height: Option<u32>,
+    pub end_height: Option<u32>,
+    // Number of blocks requested to the node at the same time,
+    // blocks are still saved in order.
+    #[serde(default = "default_fetch_concurrency")]
+    pub fetch_concurrency: usize,
+    // Time in milliseconds to wait after a failed request to the node,
+    // doubled on every consecutive failure up to backoff_max_ms.
+    #[serde(default = "default_backoff_min_ms")]
+    pub backoff_min_ms: u64,
+    #[serde(default = "default_backoff_max_ms")]
+    pub backoff_max_ms: u64,
+    // Time in seconds between two reloads of the checksums while
+    // indexing, 0 disables the reload.
+    #[serde(default = "default_checksums_reload_interval")]
+    pub checksums_reload_interval: u64,
+    // Proof of stake parameters of the chain, in epochs, as given by
+    // `namada client query-protocol-parameters`.
+    #[serde(default = "default_pipeline_len")]
+    pub pipeline_len: u64,
+    #[serde(default = "default_unbonding_len")]
+    pub unbonding_len: u64,
+    #[serde(default = "default_cubic_slashing_window_len")]
+    pub cubic_slashing_window_len: u64,
+}
+
+const fn default_fetch_concurrency() -> usize {
+    FETCH_CONCURRENCY
+}
+
+const fn default_backoff_min_ms() -> u64 {
+    BACKOFF_MIN_MS
+}
+
+const fn default_backoff_max_ms() -> u64 {
+    BACKOFF_MAX_MS
+}
+
+const fn default_checksums_reload_interval() -> u64 {
+    CHECKSUMS_RELOAD_INTERVAL
+}
+
+const fn default_pipeline_len() -> u64 {
+    PIPELINE_LEN
+}
+
+const fn default_unbonding_len() -> u64 {
+    UNBONDING_LEN
+}
+
+const fn default_cubic_slashing_window_len() -> u64 {
+    CUBIC_SLASHING_WINDOW_LEN
+}
+
+const fn default_rpc_max_failures() -> u32 {
+    RPC_MAX_FAILURES
+}
+
+const fn default_rpc_max_height_lag() -> u64 {
+    RPC_MAX_HEIGHT_LAG
 }
 
 #[derive(Debug, Deserialize)]
@@ -46,6 +138,10 @@ pub struct DatabaseConfig {
     pub connection_timeout: Option<u64>,
     //  Give the option to skip the index creation
     pub create_index: bool,
+    // Keep the serialized decrypted transactions so their data
+    // can be decoded again later on.
+    #[serde(default)]
+    pub store_raw_txs: bool,
 }
 
 const fn default_db_port() -> u16 {
@@ -94,6 +190,20 @@ impl Default for IndexerConfig {
     fn default() -> Self {
         Self {
             tendermint_addr: TENDERMINT_ADDR.to_owned(),
+            tendermint_addrs: vec![],
+            rpc_load_balance: false,
+            rpc_max_failures: RPC_MAX_FAILURES,
+            rpc_max_height_lag: RPC_MAX_HEIGHT_LAG,
+            websocket_addr: None,
+            start_height: None,
+            end_height: None,
+            fetch_concurrency: FETCH_CONCURRENCY,
+            backoff_min_ms: BACKOFF_MIN_MS,
+            backoff_max_ms: BACKOFF_MAX_MS,
+            checksums_reload_interval: CHECKSUMS_RELOAD_INTERVAL,
+            pipeline_len: PIPELINE_LEN,
+            unbonding_len: UNBONDING_LEN,
+            cubic_slashing_window_len: CUBIC_SLASHING_WINDOW_LEN,
         }
     }
 }
@@ -125,6 +235,7 @@ impl Default for DatabaseConfig {
             port: 5432,
             connection_timeout: None,
             create_index: true,
+            store_raw_txs: false,
         }
     }
 }
@@ -165,8 +276,34 @@ pub struct CliSettings {
     pub database_connection_timeout: Option<u64>,
     #[clap(long, env, default_value = "true")]
     pub database_create_index: bool,
+    #[clap(long, env)]
+    pub database_store_raw_txs: bool,
     #[clap(long, env, default_value = TENDERMINT_ADDR)]
     pub indexer_tendermint_addr: String,
+    #[clap(long, env, value_delimiter = ',')]
+    pub indexer_tendermint_addrs: Vec<String>,
+    #[clap(long, env)]
+    pub indexer_rpc_load_balance: bool,
+    #[clap(long, env, default_value_t = RPC_MAX_FAILURES)]
+    pub indexer_rpc_max_failures: u32,
+    #[clap(long, env, default_value_t = RPC_MAX_HEIGHT_LAG)]
+    pub indexer_rpc_max_height_lag: u64,
+    #[clap(long, env)]
+    pub indexer_websocket_addr: Option<String>,
+    #[clap(long, env, default_value_t = FETCH_CONCURRENCY)]
+    pub indexer_fetch_concurrency: usize,
+    #[clap(long, env, default_value_t = BACKOFF_MIN_MS)]
+    pub indexer_backoff_min_ms: u64,
+    #[clap(long, env, default_value_t = BACKOFF_MAX_MS)]
+    pub indexer_backoff_max_ms: u64,
+    #[clap(long, env, default_value_t = CHECKSUMS_RELOAD_INTERVAL)]
+    pub indexer_checksums_reload_interval: u64,
+    #[clap(long, env, default_value_t = PIPELINE_LEN)]
+    pub indexer_pipeline_len: u64,
+    #[clap(long, env, default_value_t = UNBONDING_LEN)]
+    pub indexer_unbonding_len: u64,
+    #[clap(long, env, default_value_t = CUBIC_SLASHING_WINDOW_LEN)]
+    pub indexer_cubic_slashing_window_len: u64,
     #[clap(long, env, action=ArgAction::SetFalse)]
     pub jaeger_enable: bool,
     #[clap(long, env, default_value = JAEGER_HOST)]
@@ -221,6 +358,7 @@ impl From<CliSettings> for Settings {
                 port: value.database_port,
                 connection_timeout: value.database_connection_timeout,
                 create_index: value.database_create_index,
+                store_raw_txs: value.database_store_raw_txs,
             },
             server: ServerConfig {
                 serve_at: value.server_serve_at,
@@ -229,6 +367,20 @@ impl From<CliSettings> for Settings {
             },
             indexer: IndexerConfig {
                 tendermint_addr: value.indexer_tendermint_addr,
+                tendermint_addrs: value.indexer_tendermint_addrs,
+                rpc_load_balance: value.indexer_rpc_load_balance,
+                rpc_max_failures: value.indexer_rpc_max_failures,
+                rpc_max_height_lag: value.indexer_rpc_max_height_lag,
+                websocket_addr: value.indexer_websocket_addr,
+                start_height: None,
+                end_height: None,
+                fetch_concurrency: value.indexer_fetch_concurrency,
+                backoff_min_ms: value.indexer_backoff_min_ms,
+                backoff_max_ms: value.indexer_backoff_max_ms,
+                checksums_reload_interval: value.indexer_checksums_reload_interval,
+                pipeline_len: value.indexer_pipeline_len,
+                unbonding_len: value.indexer_unbonding_len,
+                cubic_slashing_window_len: value.indexer_cubic_slashing_window_len,
             },
             jaeger: JaegerConfig {
                 enable: value.jaeger_enable,
@@ -246,29 +398,50 @@ impl From<CliSettings> for Settings {
 impl Settings {
     #[instrument(level = "debug")]
     pub fn new() -> Result<Self, Error> {
+        if let Some(settings) = Self::from_config_file()? {
+            return Ok(settings);
+        }
+
+        let cli_settings = CliSettings::parse();
+        let settings = Settings::from(cli_settings);
+
+        Ok(settings)
+    }
+
+    /// Same as [Settings::new] but using already parsed command line
+    /// settings, for binaries having their own arguments.
+    #[instrument(level = "debug", skip(cli_settings))]
+    pub fn with_cli_settings(cli_settings: CliSettings) -> Result<Self, Error> {
+        if let Some(settings) = Self::from_config_file()? {
+            return Ok(settings);
+        }
+
+        Ok(Settings::from(cli_settings))
+    }
+
+    fn from_config_file() -> Result<Option<Self>, Error> {
         // Try to read INDEXER_CONFIG_PATH env variable
         // otherwise use default settings.
-        if let Ok(path) = env::var(ENV_VAR_NAME) {
-            debug!("Reading configuration file from {}", path);
+        let Ok(path) = env::var(ENV_VAR_NAME) else {
+            return Ok(None);
+        };
 
-            let config = Config::builder()
-                .add_source(File::with_name(&path))
-                .build()?;
+        debug!("Reading configuration file from {}", path);
 
-            let settings: Self = config.try_deserialize().map_err(Error::from)?;
+        let config = Config::builder()
+            .add_source(File::with_name(&path))
+            .build()?;
 
-            // verify if chain_name is correct
-            if settings.chain_name.contains('.') {
-                panic!("chain_name cannot contains '.' (example of valid chain_name 'public-testnet-14')")
-            }
+        let settings: Self = config.try_deserialize().map_err(Error::from)?;
 
-            return Ok(settings);
+        // verify if chain_name is correct
+        if settings.chain_name.contains('.') {
+            panic!(
+                "chain_name cannot contains '.' (example of valid chain_name 'public-testnet-14')"
+            )
         }
 
-        let cli_settings = CliSettings::parse();
-        let settings = Settings::from(cli_settings);
-
-        Ok(settings)
+        Ok(Some(settings))
     }
 
     pub fn server_config(&self) -> &ServerConfig {
@@ -1,36 +1,32 @@
+use crate::checksums::{ChecksumRange, Checksums};
+use crate::decoders::{ibc::IbcTransfer, DecodedTx, DecoderRegistry};
 use crate::queries::insert_block_query;
 use crate::{config::DatabaseConfig, error::Error, utils};
 use serde_json::json;
 
-use namada_sdk::types::key::common::PublicKey;
 use namada_sdk::{
-    account::{InitAccount, UpdateAccount},
-    borsh::BorshDeserialize,
     governance::{InitProposalData, VoteProposalData},
     tx::{
         data::{
-            pgf::UpdateStewardCommission,
-            pos::{
-                BecomeValidator, Bond, CommissionChange, ConsensusKeyChange, MetaDataChange,
-                Redelegation, Unbond, Withdraw,
-            },
+            pos::{Bond, Redelegation, Withdraw},
             TxType,
         },
         Tx,
     },
     types::{
         address::Address,
-        eth_bridge_pool::PendingTransfer,
-        // key::PublicKey,
+        hash::Hash,
+        key::{common::PublicKey, tm_consensus_key_raw_hash},
         token,
     },
 };
 use sqlx::postgres::{PgPool, PgPoolOptions, PgRow as Row};
 use sqlx::Row as TRow;
 use sqlx::{query, QueryBuilder, Transaction};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 use std::time::Duration;
+use tendermint::abci::Event;
 use tendermint::block::Block;
 use tendermint_proto::types::evidence::Sum;
 use tendermint_proto::types::CommitSig;
@@ -40,12 +36,29 @@ use tracing::{debug, info, instrument};
 
 use crate::{
     DB_SAVE_BLOCK_COUNTER, DB_SAVE_BLOCK_DURATION, DB_SAVE_COMMIT_SIG_DURATION,
-    DB_SAVE_EVDS_DURATION, DB_SAVE_TXS_DURATION, INDEXER_LAST_SAVE_BLOCK_HEIGHT, MASP_ADDR,
+    DB_SAVE_EVDS_DURATION, DB_SAVE_TXS_DURATION, INDEXER_DECODE_FAILURE_COUNTER,
+    INDEXER_LAST_SAVE_BLOCK_HEIGHT, INDEXER_ROLLBACK_COUNTER, MASP_ADDR, NATIVE_TOKEN_ADDR,
+    NATIVE_TOKEN_DECIMALS,
 };
 
 use crate::tables::{
-    get_create_block_table_query, get_create_commit_signatures_table_query,
-    get_create_evidences_table_query, get_create_transactions_table_query,
+    get_create_balance_changes_table_query, get_create_block_table_query,
+    get_create_bond_changes_table_query, get_create_bonds_table_query,
+    get_create_commit_signatures_table_query, get_create_decode_failures_table_query,
+    get_create_events_table_query, get_create_evidences_table_query,
+    get_create_genesis_validators_table_query, get_create_ibc_transfers_table_query,
+    get_create_pos_parameters_table_query, get_create_proposals_table_query,
+    get_create_redelegations_table_query, get_create_reward_claims_table_query,
+    get_create_transactions_table_query, get_create_transfers_table_query,
+    get_create_tx_checksums_table_query, get_create_tx_raw_table_query,
+    get_create_tx_wrappers_table_query, get_create_unbond_withdrawals_table_query,
+    get_create_unbonds_table_query, get_create_validator_commissions_table_query,
+    get_create_validator_consensus_keys_table_query, get_create_validator_metadata_table_query,
+    get_create_validator_states_table_query, get_create_validator_tm_addresses_table_query,
+    get_create_validators_view_query, get_create_votes_table_query,
+    get_create_withdrawals_table_query, get_drop_validator_tm_addresses_view_query,
+    get_migrate_transactions_table_query, get_migrate_tx_wrappers_query,
+    get_migrate_unbonds_table_query, get_refresh_validator_tm_addresses_query,
 };
 use crate::views;
 
@@ -53,6 +66,113 @@ use metrics::{gauge, histogram, increment_counter};
 
 const BLOCKS_TABLE_NAME: &str = "blocks";
 const TX_TABLE_NAME: &str = "transactions";
+const EVIDENCES_TABLE_NAME: &str = "evidences";
+const COMMIT_SIGNATURES_TABLE_NAME: &str = "commit_signatures";
+const TX_CHECKSUMS_TABLE_NAME: &str = "tx_checksums";
+const DECODE_FAILURES_TABLE_NAME: &str = "decode_failures";
+const EVENTS_TABLE_NAME: &str = "events";
+const TX_WRAPPERS_TABLE_NAME: &str = "tx_wrappers";
+const TX_RAW_TABLE_NAME: &str = "tx_raw";
+const IBC_TRANSFERS_TABLE_NAME: &str = "ibc_transfers";
+const TRANSFERS_TABLE_NAME: &str = "transfers";
+pub(crate) const BONDS_TABLE_NAME: &str = "bonds";
+pub(crate) const UNBONDS_TABLE_NAME: &str = "unbonds";
+const REDELEGATIONS_TABLE_NAME: &str = "redelegations";
+const WITHDRAWALS_TABLE_NAME: &str = "withdrawals";
+const REWARD_CLAIMS_TABLE_NAME: &str = "reward_claims";
+const VOTES_TABLE_NAME: &str = "votes";
+const BALANCE_CHANGES_TABLE_NAME: &str = "balance_changes";
+const BOND_CHANGES_TABLE_NAME: &str = "bond_changes";
+const UNBOND_WITHDRAWALS_TABLE_NAME: &str = "unbond_withdrawals";
+const PROPOSALS_TABLE_NAME: &str = "proposals";
+const VALIDATOR_STATES_TABLE_NAME: &str = "validator_states";
+const VALIDATOR_COMMISSIONS_TABLE_NAME: &str = "validator_commissions";
+const VALIDATOR_METADATA_TABLE_NAME: &str = "validator_metadata";
+const VALIDATOR_CONSENSUS_KEYS_TABLE_NAME: &str = "validator_consensus_keys";
+const VALIDATORS_VIEW_NAME: &str = "validators";
+const GENESIS_VALIDATORS_TABLE_NAME: &str = "genesis_validators";
+const POS_PARAMETERS_TABLE_NAME: &str = "pos_parameters";
+const VALIDATOR_TM_ADDRESSES_TABLE_NAME: &str = "validator_tm_addresses";
+
+// Tables of the decoders keeping the epoch of the transaction, apart from
+// the withdrawals which depend on it.
+const TX_EPOCH_TABLES: [&str; 12] = [
+    TRANSFERS_TABLE_NAME,
+    BONDS_TABLE_NAME,
+    UNBONDS_TABLE_NAME,
+    REDELEGATIONS_TABLE_NAME,
+    REWARD_CLAIMS_TABLE_NAME,
+    VOTES_TABLE_NAME,
+    BOND_CHANGES_TABLE_NAME,
+    PROPOSALS_TABLE_NAME,
+    VALIDATOR_STATES_TABLE_NAME,
+    VALIDATOR_COMMISSIONS_TABLE_NAME,
+    VALIDATOR_METADATA_TABLE_NAME,
+    VALIDATOR_CONSENSUS_KEYS_TABLE_NAME,
+];
+
+// First epoch the tokens of an unbond `u` can be withdrawn at, once the
+// unbond is effective and can no longer be slashed, `p` being the row of
+// the proof of stake parameters.
+const WITHDRAWABLE_EPOCH: &str =
+    "u.epoch + p.pipeline_len + p.unbonding_len + p.cubic_slashing_window_len";
+
+// Values of the block_id_flag of a commit signature, a validator
+// without any signature in a commit is absent too.
+pub(crate) const BLOCK_ID_FLAG_COMMIT: i32 = 2;
+pub(crate) const BLOCK_ID_FLAG_NIL: i32 = 3;
+
+// Columns of the validators view, with the numeric ones as text.
+const VALIDATOR_COLUMNS: &str = "address, registered_height, state, consensus_key,
+    commission_rate::TEXT AS commission_rate,
+    max_commission_rate_change::TEXT AS max_commission_rate_change,
+    email, description, website, discord_handle, avatar";
+
+// Tables holding data of a block, all referencing it by block_id.
+const BLOCK_DATA_TABLES: [&str; 23] = [
+    TX_TABLE_NAME,
+    EVIDENCES_TABLE_NAME,
+    COMMIT_SIGNATURES_TABLE_NAME,
+    DECODE_FAILURES_TABLE_NAME,
+    EVENTS_TABLE_NAME,
+    TX_WRAPPERS_TABLE_NAME,
+    TX_RAW_TABLE_NAME,
+    IBC_TRANSFERS_TABLE_NAME,
+    TRANSFERS_TABLE_NAME,
+    BONDS_TABLE_NAME,
+    UNBONDS_TABLE_NAME,
+    REDELEGATIONS_TABLE_NAME,
+    WITHDRAWALS_TABLE_NAME,
+    REWARD_CLAIMS_TABLE_NAME,
+    VOTES_TABLE_NAME,
+    BALANCE_CHANGES_TABLE_NAME,
+    BOND_CHANGES_TABLE_NAME,
+    UNBOND_WITHDRAWALS_TABLE_NAME,
+    PROPOSALS_TABLE_NAME,
+    VALIDATOR_STATES_TABLE_NAME,
+    VALIDATOR_COMMISSIONS_TABLE_NAME,
+    VALIDATOR_METADATA_TABLE_NAME,
+    VALIDATOR_CONSENSUS_KEYS_TABLE_NAME,
+];
+
+// Max number of events inserted by a single query, each event
+// takes 8 of the 65535 parameters postgres allows.
+const EVENTS_BATCH_SIZE: usize = 4096;
+
+// A transaction that could not be decoded:
+// (hash, tx_type, code, data, error)
+type DecodeFailure = (Vec<u8>, Option<String>, Option<Vec<u8>>, Vec<u8>, String);
+
+// An event of the block results:
+// (source, event_index, tx_index, tx_hash, event_type, attributes)
+type EventRow = (
+    &'static str,
+    i32,
+    Option<i32>,
+    Option<Vec<u8>>,
+    String,
+    serde_json::Value,
+);
 
 // Max time to wait for a succesfull database connection
 const DATABASE_TIMEOUT: u64 = 60;
@@ -62,6 +182,8 @@ pub struct Database {
     pool: Arc<PgPool>,
     // we use the network as the name of the schema to allow diffrent net on the same database
     pub network: String,
+    // keep the serialized decrypted transactions in tx_raw
+    store_raw_txs: bool,
 }
 
 impl Database {
@@ -99,6 +221,7 @@ impl Database {
         Ok(Database {
             pool: Arc::new(pool),
             network: network_schema,
+            store_raw_txs: db_config.store_raw_txs,
         })
     }
 
@@ -106,6 +229,7 @@ impl Database {
         Self {
             pool: Arc::new(pool),
             network,
+            store_raw_txs: false,
         }
     }
 
@@ -120,6 +244,30 @@ impl Database {
     /// - `transactions` although part of the block data, they are store in a different table
     /// and contain useful information about transactions.
     /// - `evidences` Where block's evidence data is stored.
+    /// - `tx_checksums` the wasm code hashes of each transaction type
+    /// and the heights they are valid for.
+    /// - `decode_failures` the transactions that could not be decoded.
+    /// - `events` the events found in the block results.
+    /// - `tx_wrappers` links the wrappers to their decrypted transaction.
+    /// - `tx_raw` the serialized decrypted transactions, only filled if
+    /// `store_raw_txs` is enabled.
+    /// - `ibc_transfers` the fungible token transfers sent or received
+    /// through IBC.
+    /// - `transfers`, `bonds`, `unbonds`, `redelegations`, `withdrawals`
+    /// and `votes` the decoded data of the successful transactions of
+    /// these types.
+    /// - `balance_changes` the changes of the balances of the addresses,
+    /// by token.
+    /// - `bond_changes` the changes of the amounts bonded by the delegators
+    /// to the validators.
+    /// - `unbond_withdrawals` the unbonds withdrawn by a tx_withdraw.
+    /// - `proposals` the governance proposals made by tx_init_proposal.
+    /// - `validator_states`, `validator_commissions`, `validator_metadata`
+    /// and `validator_consensus_keys` the history of the validators, the
+    /// `validators` view giving their current state.
+    /// - `genesis_validators` the consensus keys of the validators created
+    /// at genesis, the `validator_tm_addresses` table mapping the Tendermint
+    /// addresses of all the consensus keys to their validator.
     #[instrument(skip(self))]
     pub async fn create_tables(&self) -> Result<(), Error> {
         info!("Creating tables if they don't exist");
@@ -144,6 +292,124 @@ impl Database {
             .execute(&*self.pool)
             .await?;
 
+        query(get_create_tx_checksums_table_query(&self.network).as_str())
+            .execute(&*self.pool)
+            .await?;
+
+        query(get_create_decode_failures_table_query(&self.network).as_str())
+            .execute(&*self.pool)
+            .await?;
+
+        query(get_create_events_table_query(&self.network).as_str())
+            .execute(&*self.pool)
+            .await?;
+
+        query(get_create_tx_wrappers_table_query(&self.network).as_str())
+            .execute(&*self.pool)
+            .await?;
+
+        // the links kept in the `wrapper_id` column of an older
+        // `transactions` table are moved before the column is dropped
+        query(get_migrate_tx_wrappers_query(&self.network).as_str())
+            .execute(&*self.pool)
+            .await?;
+
+        query(get_migrate_transactions_table_query(&self.network).as_str())
+            .execute(&*self.pool)
+            .await?;
+
+        query(get_create_tx_raw_table_query(&self.network).as_str())
+            .execute(&*self.pool)
+            .await?;
+
+        query(get_create_ibc_transfers_table_query(&self.network).as_str())
+            .execute(&*self.pool)
+            .await?;
+
+        query(get_create_transfers_table_query(&self.network).as_str())
+            .execute(&*self.pool)
+            .await?;
+
+        query(get_create_bonds_table_query(&self.network).as_str())
+            .execute(&*self.pool)
+            .await?;
+
+        query(get_create_unbonds_table_query(&self.network).as_str())
+            .execute(&*self.pool)
+            .await?;
+
+        query(get_migrate_unbonds_table_query(&self.network).as_str())
+            .execute(&*self.pool)
+            .await?;
+
+        query(get_create_redelegations_table_query(&self.network).as_str())
+            .execute(&*self.pool)
+            .await?;
+
+        query(get_create_withdrawals_table_query(&self.network).as_str())
+            .execute(&*self.pool)
+            .await?;
+
+        query(get_create_reward_claims_table_query(&self.network).as_str())
+            .execute(&*self.pool)
+            .await?;
+
+        query(get_create_votes_table_query(&self.network).as_str())
+            .execute(&*self.pool)
+            .await?;
+
+        query(get_create_balance_changes_table_query(&self.network).as_str())
+            .execute(&*self.pool)
+            .await?;
+
+        query(get_create_bond_changes_table_query(&self.network).as_str())
+            .execute(&*self.pool)
+            .await?;
+
+        query(get_create_unbond_withdrawals_table_query(&self.network).as_str())
+            .execute(&*self.pool)
+            .await?;
+
+        query(get_create_proposals_table_query(&self.network).as_str())
+            .execute(&*self.pool)
+            .await?;
+
+        query(get_create_validator_states_table_query(&self.network).as_str())
+            .execute(&*self.pool)
+            .await?;
+
+        query(get_create_validator_commissions_table_query(&self.network).as_str())
+            .execute(&*self.pool)
+            .await?;
+
+        query(get_create_validator_metadata_table_query(&self.network).as_str())
+            .execute(&*self.pool)
+            .await?;
+
+        query(get_create_validator_consensus_keys_table_query(&self.network).as_str())
+            .execute(&*self.pool)
+            .await?;
+
+        query(get_create_genesis_validators_table_query(&self.network).as_str())
+            .execute(&*self.pool)
+            .await?;
+
+        query(get_create_pos_parameters_table_query(&self.network).as_str())
+            .execute(&*self.pool)
+            .await?;
+
+        query(get_create_validators_view_query(&self.network).as_str())
+            .execute(&*self.pool)
+            .await?;
+
+        query(get_drop_validator_tm_addresses_view_query(&self.network).as_str())
+            .execute(&*self.pool)
+            .await?;
+
+        query(get_create_validator_tm_addresses_table_query(&self.network).as_str())
+            .execute(&*self.pool)
+            .await?;
+
         // And views
         query(views::get_create_tx_become_validator_view_query(&self.network).as_str())
             .execute(&*self.pool)
@@ -236,15 +502,29 @@ impl Database {
         Ok(())
     }
 
+    /// Create the tables the decoders save their own data to.
+    #[instrument(skip_all)]
+    pub async fn create_decoder_tables(&self, decoders: &DecoderRegistry) -> Result<(), Error> {
+        for decoder in decoders.iter() {
+            for table_query in decoder.create_tables(&self.network) {
+                query(&table_query).execute(&*self.pool).await?;
+            }
+        }
+
+        Ok(())
+    }
+
     /// Inner implementation that uses a postgres-transaction
     /// to ensure database coherence.
-    #[instrument(skip(block, block_results, checksums_map, sqlx_tx))]
+    #[instrument(skip(block, block_results, checksums, decoders, sqlx_tx))]
     async fn save_block_impl<'a>(
         block: &Block,
         block_results: &block_results::Response,
-        checksums_map: &HashMap<String, String>,
+        checksums: &Checksums,
+        decoders: &DecoderRegistry,
         sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
         network: &str,
+        store_raw_txs: bool,
     ) -> Result<(), Error> {
         // let mut query_builder: QueryBuilder<_> = QueryBuilder::new(insert_block_query(network));
 
@@ -333,12 +613,30 @@ impl Database {
 
         let evidence_list = RawEvidenceList::from(block.evidence().clone());
         Self::save_evidences(evidence_list, block_id, sqlx_tx, network).await?;
-        Self::save_transactions(
+        Self::save_events(
+            block_id,
+            block.header.height.value(),
+            block_results,
+            sqlx_tx,
+            network,
+        )
+        .await?;
+        let block_epoch = Self::save_transactions(
             block.data.as_ref(),
             block_id,
             block.header.height.value(),
             block_results,
-            checksums_map,
+            checksums,
+            decoders,
+            sqlx_tx,
+            network,
+            store_raw_txs,
+        )
+        .await?;
+        Self::update_validator_tm_addresses(
+            block_id,
+            block.header.height.value(),
+            block_epoch,
             sqlx_tx,
             network,
         )
@@ -348,12 +646,13 @@ impl Database {
     }
 
     /// Save a block and commit database
-    #[instrument(skip(self, block, block_results, checksums_map))]
+    #[instrument(skip(self, block, block_results, checksums, decoders))]
     pub async fn save_block(
         &self,
         block: &Block,
         block_results: &block_results::Response,
-        checksums_map: &HashMap<String, String>,
+        checksums: &Checksums,
+        decoders: &DecoderRegistry,
     ) -> Result<(), Error> {
         let instant = tokio::time::Instant::now();
         // Lets use postgres transaction internally for 2 reasons:
@@ -368,9 +667,11 @@ impl Database {
         Self::save_block_impl(
             block,
             block_results,
-            checksums_map,
+            checksums,
+            decoders,
             &mut sqlx_tx,
             self.network.as_str(),
+            self.store_raw_txs,
         )
         .await?;
 
@@ -501,15 +802,26 @@ impl Database {
     /// It is up to the caller to commit the operation.
     /// this method is meant to be used when caller is saving
     /// many blocks, and can commit after it.
-    #[instrument(skip(block, block_results, checksums_map, sqlx_tx, network))]
+    #[instrument(skip(block, block_results, checksums, decoders, sqlx_tx, network))]
     pub async fn save_block_tx<'a>(
         block: &Block,
         block_results: &block_results::Response,
-        checksums_map: &HashMap<String, String>,
+        checksums: &Checksums,
+        decoders: &DecoderRegistry,
         sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
         network: &str,
+        store_raw_txs: bool,
     ) -> Result<(), Error> {
-        Self::save_block_impl(block, block_results, checksums_map, sqlx_tx, network).await
+        Self::save_block_impl(
+            block,
+            block_results,
+            checksums,
+            decoders,
+            sqlx_tx,
+            network,
+            store_raw_txs,
+        )
+        .await
     }
 
     /// Save all the evidences in the list, it is up to the caller to
@@ -620,17 +932,19 @@ impl Database {
 
     /// Save all the transactions in txs, it is up to the caller to
     /// call sqlx_tx.commit().await?; for the changes to take place in
-    /// database.
-    #[instrument(skip(txs, block_id, sqlx_tx, checksums_map, block_results, network))]
+    /// database. Returns the greatest epoch of the wrappers of the block.
+    #[instrument(skip(txs, block_id, sqlx_tx, checksums, decoders, block_results, network))]
     async fn save_transactions<'a>(
         txs: &[Vec<u8>],
         block_id: &[u8],
         block_height: u64,
         block_results: &block_results::Response,
-        checksums_map: &HashMap<String, String>,
+        checksums: &Checksums,
+        decoders: &DecoderRegistry,
         sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
         network: &str,
-    ) -> Result<(), Error> {
+        store_raw_txs: bool,
+    ) -> Result<Option<u64>, Error> {
         // use for metrics
         let instant = tokio::time::Instant::now();
 
@@ -644,7 +958,7 @@ impl Database {
             let dur = instant.elapsed();
 
             histogram!(DB_SAVE_TXS_DURATION, dur.as_secs_f64() * 1000.0, &labels);
-            return Ok(());
+            return Ok(None);
         }
 
         debug!(message = "Saving transactions");
@@ -654,200 +968,163 @@ impl Database {
                     hash, 
                     block_id, 
                     tx_type,
-                    wrapper_id,
                     fee_amount_per_gas_unit,
                     fee_token,
                     gas_limit_multiplier,
                     code,
                     data,
-                    return_code
+                    return_code,
+                    gas_used,
+                    info,
+                    log,
+                    epoch,
+                    fee_payer
                 )",
             network
         ));
 
-        // this will holds tuples (hash, block_id, tx_type, fee_amount_per_gas_unit, fee_token, gas_limit_multiplier, code, data)
+        // this will holds tuples (hash, block_id, tx_type, fee_amount_per_gas_unit, fee_token, gas_limit_multiplier, code, data, return_code, gas_used, info, log, epoch, fee_payer)
         // in order to push txs.len at once in a single query.
         // the limit for bind values in postgres is 65535 values, that means that
         // to hit that limit a block would need to have:
-        // n_tx = 65535/8 = 8191
-        // being 8 the number of columns.
+        // n_tx = 65535/14 = 4681
+        // being 14 the number of columns.
         let mut tx_values = Vec::with_capacity(txs.len());
 
-        let mut i: usize = 0;
-        for t in txs.iter() {
-            let tx = Tx::try_from(t.as_slice()).map_err(|e| Error::InvalidTxData(e.to_string()))?;
+        // transactions that could not be decoded, saved as they are
+        let mut decode_failures: Vec<DecodeFailure> = Vec::new();
+
+        // (wrapper_hash, inner_hash) of the wrappers in this block
+        let mut wrappers: Vec<(Vec<u8>, Vec<u8>)> = Vec::new();
+
+        // (hash, index in the block, raw) of the decrypted transactions if
+        // they are kept
+        let mut raw_txs: Vec<(Vec<u8>, i32, Vec<u8>)> = Vec::new();
+
+        // greatest epoch of the wrappers in this block
+        let mut block_epoch: Option<u64> = None;
+
+        // (hash, tx_type, code, data, raw data, tx) of the transactions decoded successfully
+        let mut decoded: Vec<(Vec<u8>, String, Vec<u8>, serde_json::Value, Vec<u8>, Tx)> =
+            Vec::new();
+
+        for (i, t) in txs.iter().enumerate() {
+            let tx = match Tx::try_from(t.as_slice()) {
+                Ok(tx) => tx,
+                Err(e) => {
+                    // without a header there is no hash to identify the tx,
+                    // use the hash of its bytes like tendermint does.
+                    tracing::warn!("Failed to decode transaction: {}", e);
+                    increment_counter!(INDEXER_DECODE_FAILURE_COUNTER, "tx_type" => "unknown");
+                    decode_failures.push((
+                        Hash::sha256(t).0.to_vec(),
+                        None,
+                        None,
+                        t.clone(),
+                        e.to_string(),
+                    ));
+                    continue;
+                }
+            };
 
             let mut code = Default::default();
-            let mut txid_wrapper: Vec<u8> = vec![];
             let mut hash_id = tx.header_hash().to_vec();
             let mut data_json: serde_json::Value = json!(null);
             let mut return_code: Option<i32> = None;
 
-            // Decrypted transaction give access to the raw data
             if let TxType::Decrypted(..) = tx.header().tx_type {
                 // For unknown reason the header has to be updated before hashing it for its id (https://github.com/Zondax/namadexer/issues/23)
                 hash_id = tx.clone().update_header(TxType::Raw).header_hash().to_vec();
+            }
 
-                // Look for the return code in the block results
-                let end_events = block_results.end_block_events.clone().unwrap(); // Safe to use unwrap because if it is not present then something is broken.
-
-                // Look for the reurn code associated to the tx
-                for event in end_events {
-                    for attr in event.attributes.iter() {
-                        // We look to confirm hash of transaction
-                        if attr.key == "hash"
-                            && attr.value.to_ascii_lowercase() == hex::encode(&hash_id)
-                        {
-                            // Now we look for the return code
-                            for attr in event.attributes.iter() {
-                                if attr.key == "code" {
-                                    // using unwrap here is ok because we assume it is always going to be a number unless there is a bug in the node
-                                    return_code = Some(attr.value.parse().unwrap());
-                                }
-                            }
-                        }
-                    }
-                }
+            // Look for the execution result of the tx in the block results
+            let result = TxResult::find(block_results, &hash_id);
 
-                // look for wrapper tx to link to
-                let txs = query(&format!("SELECT * FROM {0}.transactions WHERE block_id IN (SELECT block_id FROM {0}.blocks WHERE header_height = {1});", network, block_height-1))
-                    .fetch_all(&mut *sqlx_tx)
-                    .await?;
-                txid_wrapper = txs[i].try_get("hash")?;
-                i += 1;
+            // Decrypted transaction give access to the raw data
+            if let TxType::Decrypted(..) = tx.header().tx_type {
+                return_code = result.code;
 
-                code = tx
+                code = match tx
                     .get_section(tx.code_sechash())
                     .and_then(|s| s.code_sec())
                     .map(|s| s.code.hash().0)
-                    .ok_or(Error::InvalidTxData("no code hash".into()))?;
+                {
+                    Some(code) => code,
+                    None => {
+                        // without its code the transaction type is unknown,
+                        // save it without code nor data instead of failing
+                        // the whole block.
+                        tracing::warn!("Missing code section in transaction");
+                        increment_counter!(INDEXER_DECODE_FAILURE_COUNTER, "tx_type" => "unknown");
+                        decode_failures.push((
+                            hash_id.clone(),
+                            None,
+                            None,
+                            tx.data().unwrap_or_default(),
+                            "no code hash".to_string(),
+                        ));
+                        tx_values.push((
+                            hash_id,
+                            block_id.to_vec(),
+                            utils::tx_type_name(&tx.header.tx_type),
+                            None,
+                            None,
+                            None,
+                            None,
+                            data_json,
+                            return_code,
+                            result.gas_used,
+                            result.info,
+                            result.log,
+                            None,
+                            None,
+                        ));
+                        continue;
+                    }
+                };
+
+                if store_raw_txs {
+                    raw_txs.push((hash_id.clone(), i as i32, t.clone()));
+                }
 
                 let code_hex = hex::encode(code.as_slice());
-                let unknown_type = "unknown".to_string();
-                let type_tx = checksums_map.get(&code_hex).unwrap_or(&unknown_type);
+                let type_tx = checksums
+                    .tx_type(&code_hex, block_height)
+                    .unwrap_or("unknown");
 
                 // decode tx_transfer, tx_bond and tx_unbound to store the decoded data in their tables
                 // if the transaction has failed don't try to decode because the changes are not included and the data might not be correct
-                if return_code.unwrap() == 0 {
-                    let data = tx
-                        .data()
-                        .ok_or(Error::InvalidTxData("tx has no data".into()))?;
-
-                    dbg!(type_tx.as_str());
+                if return_code == Some(0) {
+                    let data = tx.data().unwrap_or_default();
 
                     info!("Saving {} transaction", type_tx);
 
                     // decode tx_transfer, tx_bond and tx_unbound to store the decoded data in their tables
-                    match type_tx.as_str() {
-                        "tx_transfer" => {
-                            let transfer = token::Transfer::try_from_slice(&data[..])?;
-                            data_json = serde_json::to_value(transfer)?;
-                        }
-                        "tx_bond" => {
-                            let bond = Bond::try_from_slice(&data[..])?;
-                            data_json = serde_json::to_value(bond)?;
-                        }
-                        "tx_unbond" => {
-                            let unbond = Unbond::try_from_slice(&data[..])?;
-                            data_json = serde_json::to_value(unbond)?;
-                        }
-                        // this is an ethereum transaction
-                        "tx_bridge_pool" => {
-                            // Only TransferToEthereum type is supported at the moment by namada and us.
-                            let tx_bridge = PendingTransfer::try_from_slice(&data[..])?;
-                            data_json = serde_json::to_value(tx_bridge)?;
-                        }
-                        "tx_vote_proposal" => {
-                            let tx_vote_proposal = VoteProposalData::try_from_slice(&data[..])?;
-                            data_json = serde_json::to_value(tx_vote_proposal)?;
-                        }
-                        "tx_reveal_pk" => {
-                            // nothing to do here, only check that data is a valid publicKey
-                            // otherwise this transaction must not make it into
-                            // the database.
-                            let tx_reveal_pk = PublicKey::try_from_slice(&data[..])?;
-                            data_json = serde_json::to_value(tx_reveal_pk)?;
-                        }
-                        "tx_resign_steward" => {
-                            // Not much to do, just, check that the address this transactions
-                            // holds in the data field is correct, or at least parsed succesfully.
-                            let tx_resign_steward = Address::try_from_slice(&data[..])?;
-                            data_json = serde_json::to_value(tx_resign_steward)?;
-                        }
-                        "tx_update_steward_commission" => {
-                            // Not much to do, just, check that the address this transactions
-                            // holds in the data field is correct, or at least parsed succesfully.
-                            let tx_update_steward_commission =
-                                UpdateStewardCommission::try_from_slice(&data[..])?;
-                            data_json = serde_json::to_value(tx_update_steward_commission)?;
-                        }
-                        "tx_init_account" => {
-                            // check that transaction can be parsed
-                            // before inserting it into database.
-                            // later accounts could be updated using
-                            // tx_update_account, however there is not way
-                            // so far to link those transactions to this.
-                            let tx_init_account = InitAccount::try_from_slice(&data[..])?;
-                            data_json = serde_json::to_value(tx_init_account)?;
-                        }
-                        "tx_update_account" => {
-                            // check that transaction can be parsed
-                            // before storing it into database
-                            let tx_update_account = UpdateAccount::try_from_slice(&data[..])?;
-                            data_json = serde_json::to_value(tx_update_account)?;
-                        }
-                        "tx_ibc" => {
-                            info!("we do not handle ibc transaction yet");
-                            data_json = serde_json::to_value(hex::encode(&data[..]))?;
-                        }
-                        "tx_become_validator" => {
-                            let tx_become_validator = BecomeValidator::try_from_slice(&data[..])?;
-                            data_json = serde_json::to_value(tx_become_validator)?;
-                        }
-                        "tx_change_consensus_key" => {
-                            let tx_change_consensus_key =
-                                ConsensusKeyChange::try_from_slice(&data[..])?;
-                            data_json = serde_json::to_value(tx_change_consensus_key)?;
+                    match decoders.decode(type_tx, &data) {
+                        Ok(value) => {
+                            decoded.push((
+                                hash_id.clone(),
+                                type_tx.to_string(),
+                                code.to_vec(),
+                                value.clone(),
+                                data,
+                                tx.clone(),
+                            ));
+                            data_json = value;
                         }
-                        "tx_change_validator_commission" => {
-                            let tx_change_validator_commission =
-                                CommissionChange::try_from_slice(&data[..])?;
-                            data_json = serde_json::to_value(tx_change_validator_commission)?;
+                        Err(e) => {
+                            // keep indexing, the transaction is saved without its
+                            // data and can be decoded again later on.
+                            tracing::warn!("Failed to decode {} transaction: {}", type_tx, e);
+                            increment_counter!(INDEXER_DECODE_FAILURE_COUNTER, "tx_type" => type_tx.to_string());
+                            decode_failures.push((
+                                hash_id.clone(),
+                                Some(type_tx.to_string()),
+                                Some(code.to_vec()),
+                                data,
+                                e.to_string(),
+                            ));
                         }
-                        "tx_change_validator_metadata" => {
-                            let tx_change_validator_metadata =
-                                MetaDataChange::try_from_slice(&data[..])?;
-                            data_json = serde_json::to_value(tx_change_validator_metadata)?;
-                        }
-                        "tx_claim_rewards" => {
-                            let tx_claim_rewards = Withdraw::try_from_slice(&data[..])?;
-                            data_json = serde_json::to_value(tx_claim_rewards)?;
-                        }
-                        "tx_deactivate_validator" => {
-                            let tx_deactivate_validator = Address::try_from_slice(&data[..])?;
-                            data_json = serde_json::to_value(tx_deactivate_validator)?;
-                        }
-                        "tx_init_proposal" => {
-                            let tx_init_proposal = InitProposalData::try_from_slice(&data[..])?;
-                            data_json = serde_json::to_value(tx_init_proposal)?;
-                        }
-                        "tx_reactivate_validator" => {
-                            let tx_reactivate_validator = Address::try_from_slice(&data[..])?;
-                            data_json = serde_json::to_value(tx_reactivate_validator)?;
-                        }
-                        "tx_unjail_validator" => {
-                            let tx_unjail_validator = Address::try_from_slice(&data[..])?;
-                            data_json = serde_json::to_value(tx_unjail_validator)?;
-                        }
-                        "tx_redelegate" => {
-                            let tx_redelegate = Redelegation::try_from_slice(&data[..])?;
-                            data_json = serde_json::to_value(tx_redelegate)?;
-                        }
-                        "tx_withdraw" => {
-                            let tx_withdraw = Withdraw::try_from_slice(&data[..])?;
-                            data_json = serde_json::to_value(tx_withdraw)?;
-                        }
-                        _ => {}
                     }
                 }
             }
@@ -856,6 +1133,8 @@ impl Database {
             let mut fee_amount_per_gas_unit: Option<String> = None;
             let mut fee_token: Option<String> = None;
             let mut gas_limit_multiplier: Option<i64> = None;
+            let mut epoch: Option<i64> = None;
+            let mut fee_payer: Option<String> = None;
             if let TxType::Wrapper(txw) = tx.header().tx_type {
                 fee_amount_per_gas_unit = Some(txw.fee.amount_per_gas_unit.to_string_precise());
                 fee_token = Some(txw.fee.token.to_string());
@@ -863,24 +1142,44 @@ impl Database {
                 // WARNING! converting into i64 might ended up changing the value but there is little
                 // chance that he goes higher than i64 max value
                 gas_limit_multiplier = Some(multiplier as i64);
+                epoch = Some(txw.epoch.0 as i64);
+                block_epoch = block_epoch.max(Some(txw.epoch.0));
+                fee_payer = Some(txw.fee_payer().to_string());
+
+                // the decrypted tx is identified by the hash of the same
+                // header with a raw type, see above.
+                let inner_hash = tx.clone().update_header(TxType::Raw).header_hash().to_vec();
+                wrappers.push((hash_id.clone(), inner_hash));
             }
 
             tx_values.push((
                 hash_id,
                 block_id.to_vec(),
                 utils::tx_type_name(&tx.header.tx_type),
-                txid_wrapper,
                 fee_amount_per_gas_unit,
                 fee_token,
                 gas_limit_multiplier,
-                code,
+                Some(code),
                 data_json,
                 return_code,
+                result.gas_used,
+                result.info,
+                result.log,
+                epoch,
+                fee_payer,
             ));
         }
 
+        Self::save_decode_failures(block_id, decode_failures, sqlx_tx, network).await?;
+        Self::save_tx_wrappers(block_id, wrappers, sqlx_tx, network).await?;
+        Self::save_raw_txs(block_id, raw_txs, sqlx_tx, network).await?;
+
         let num_transactions = tx_values.len();
 
+        if num_transactions == 0 {
+            return Ok(block_epoch);
+        }
+
         // bulk insert to speed-up this
         // there might be limits regarding the number of parameter
         // but number of transaction is low in comparisson with
@@ -893,24 +1192,32 @@ impl Database {
                     hash,
                     block_id,
                     tx_type,
-                    wrapper_id,
                     fee_amount_per_gas_unit,
                     fee_token,
                     fee_gas_limit_multiplier,
                     code,
                     data,
                     return_code,
+                    gas_used,
+                    info,
+                    log,
+                    epoch,
+                    fee_payer,
                 )| {
                     b.push_bind(hash)
                         .push_bind(block_id)
                         .push_bind(tx_type)
-                        .push_bind(wrapper_id)
                         .push_bind(fee_amount_per_gas_unit)
                         .push_bind(fee_token)
                         .push_bind(fee_gas_limit_multiplier)
                         .push_bind(code)
                         .push_bind(data)
-                        .push_bind(return_code);
+                        .push_bind(return_code)
+                        .push_bind(gas_used)
+                        .push_bind(info)
+                        .push_bind(log)
+                        .push_bind(epoch)
+                        .push_bind(fee_payer);
                 },
             )
             .build()
@@ -934,98 +1241,2508 @@ impl Database {
 
         histogram!(DB_SAVE_TXS_DURATION, dur.as_secs_f64() * 1000.0, &labels);
 
-        res
-    }
+        res?;
 
-    pub async fn create_indexes(&self) -> Result<(), Error> {
-        // we create indexes on the tables to facilitate querying data
-        query(
-            format!(
-                "
-                ALTER TABLE {}.blocks ADD CONSTRAINT pk_block_id PRIMARY KEY (block_id);
-            ",
-                self.network
-            )
-            .as_str(),
-        )
-        .execute(&*self.pool)
-        .await?;
+        Self::update_fees_paid(block_id, block_height, sqlx_tx, network).await?;
+        Self::link_decrypted_txs(block_id, sqlx_tx, network).await?;
 
-        query(
-            format!(
-                "CREATE UNIQUE INDEX ux_header_height ON {}.blocks (header_height);",
-                self.network
-            )
-            .as_str(),
-        )
-        .execute(&*self.pool)
-        .await?;
+        if decoded.is_empty() {
+            return Ok(block_epoch);
+        }
 
-        // If a failed transaction is resent and successfull we don't have a unique private key in the tx hash...
-        // query(
-        //     format!(
-        //         "ALTER TABLE {}.transactions ADD CONSTRAINT pk_hash PRIMARY KEY (hash);",
-        //         self.network
-        //     )
-        //     .as_str(),
-        // )
-        // .execute(&*self.pool)
-        // .await?;
+        let hashes: Vec<Vec<u8>> = decoded.iter().map(|(hash, ..)| hash.clone()).collect();
+        let epochs = Self::wrapper_epochs(&hashes, sqlx_tx, network).await?;
 
-        query(format!("ALTER TABLE {0}.transactions ADD CONSTRAINT fk_block_id FOREIGN KEY (block_id) REFERENCES {0}.blocks (block_id);", self.network).as_str())
-            .execute(&*self.pool)
-            .await?;
+        // let the decoders store their own data, data they can't make sense
+        // of is kept as a decode failure.
+        let mut save_failures: Vec<DecodeFailure> = Vec::new();
+        for (hash, type_tx, code, data, raw, decrypted) in decoded.iter() {
+            if let Some(decoder) = decoders.get(type_tx) {
+                let tx = DecodedTx {
+                    hash,
+                    block_id,
+                    height: block_height,
+                    epoch: epochs.get(hash).copied(),
+                    data,
+                    raw,
+                    tx: decrypted,
+                };
+                match decoder.save(&tx, sqlx_tx, network).await {
+                    Ok(()) => {}
+                    Err(Error::InvalidTxData(e)) => {
+                        tracing::warn!("Failed to save {} transaction: {}", type_tx, e);
+                        increment_counter!(INDEXER_DECODE_FAILURE_COUNTER, "tx_type" => type_tx.clone());
+                        save_failures.push((
+                            hash.clone(),
+                            Some(type_tx.clone()),
+                            Some(code.clone()),
+                            raw.clone(),
+                            e,
+                        ));
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+        }
 
-        Ok(())
+        Self::save_decode_failures(block_id, save_failures, sqlx_tx, network).await?;
+
+        Ok(block_epoch)
     }
 
-    #[instrument(skip(self, block_id))]
-    pub async fn block_by_id(&self, block_id: &[u8]) -> Result<Option<Row>, Error> {
-        // query for the block if it exists
+    /// Computes the fee paid by the wrappers of the block `block_id`, the
+    /// fee amount per gas unit times the gas limit, and debits it from the
+    /// balance of their signer. It is up to the caller to call
+    /// sqlx_tx.commit().await?; for the changes to take place in database.
+    ///
+    /// Fees are paid in full when a wrapper is included in a block, so only
+    /// the wrappers saved along with the block are needed.
+    #[instrument(skip(block_id, sqlx_tx, network))]
+    async fn update_fees_paid<'a>(
+        block_id: &[u8],
+        block_height: u64,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
         let str = format!(
-            "SELECT * FROM {}.{BLOCKS_TABLE_NAME} WHERE block_id=$1",
-            self.network
+            "UPDATE {0}.{TX_TABLE_NAME}
+            SET fee_paid = fee_amount_per_gas_unit::NUMERIC * gas_limit_multiplier
+            WHERE block_id = $1
+            AND tx_type = 'Wrapper'",
+            network
         );
-        query(&str)
-            .bind(block_id)
-            .fetch_optional(&*self.pool)
-            .await
-            .map_err(Error::from)
-    }
 
-    /// Returns the block at `block_height` if present, otherwise returns an Error.
-    #[instrument(skip(self))]
-    pub async fn block_by_height(&self, block_height: u32) -> Result<Option<Row>, Error> {
+        query(&str).bind(block_id).execute(&mut *sqlx_tx).await?;
+
+        // fee_paid has the decimal places of the denomination of the
+        // fee amount, the balances are kept in the smallest unit.
         let str = format!(
-            "SELECT * FROM {}.{BLOCKS_TABLE_NAME} WHERE header_height={block_height}",
-            self.network
+            "INSERT INTO {0}.{BALANCE_CHANGES_TABLE_NAME}(
+                    block_id,
+                    height,
+                    tx_hash,
+                    address,
+                    token,
+                    amount,
+                    kind
+            )
+            SELECT $1, $2, w.hash, w.fee_payer, w.fee_token,
+                -TRUNC(w.fee_paid * POWER(10::NUMERIC, SCALE(w.fee_amount_per_gas_unit::NUMERIC))),
+                'fee'
+            FROM {0}.{TX_TABLE_NAME} w
+            WHERE w.block_id = $1
+            AND w.tx_type = 'Wrapper'
+            AND w.fee_paid IS NOT NULL
+            AND w.fee_payer IS NOT NULL",
+            network
         );
 
         query(&str)
-            .fetch_optional(&*self.pool)
-            .await
-            .map_err(Error::from)
-    }
-
-    #[instrument(skip(self))]
-    /// Returns the latest block, otherwise returns an Error.
-    pub async fn get_last_block(&self) -> Result<Row, Error> {
-        let str = format!("SELECT * FROM {0}.{BLOCKS_TABLE_NAME} WHERE header_height = (SELECT MAX(header_height) FROM {0}.{BLOCKS_TABLE_NAME})", self.network);
+            .bind(block_id)
+            .bind(block_height as i32)
+            .execute(&mut *sqlx_tx)
+            .await?;
 
-        // use query_one as the row matching max height is unique.
-        query(&str)
-            .fetch_one(&*self.pool)
-            .await
-            .map_err(Error::from)
+        Ok(())
     }
 
-    #[instrument(skip(self))]
-    /// Returns the latest height value, otherwise returns an Error.
-    pub async fn get_last_height(&self) -> Result<Row, Error> {
-        let str = format!(
-            "SELECT MAX(header_height) AS header_height FROM {}.{BLOCKS_TABLE_NAME}",
-            self.network
-        );
+    /// Save the link between the wrappers of a block and their inner
+    /// transaction, decrypted in a following block. It is up to the caller
+    /// to call sqlx_tx.commit().await?; for the changes to take place in
+    /// database.
+    #[instrument(skip_all)]
+    async fn save_tx_wrappers<'a>(
+        block_id: &[u8],
+        wrappers: Vec<(Vec<u8>, Vec<u8>)>,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        if wrappers.is_empty() {
+            return Ok(());
+        }
+
+        let mut query_builder: QueryBuilder<_> = QueryBuilder::new(format!(
+            "INSERT INTO {}.{TX_WRAPPERS_TABLE_NAME}(
+                    wrapper_hash,
+                    inner_hash,
+                    block_id
+            )",
+            network
+        ));
+
+        query_builder
+            .push_values(wrappers.into_iter(), |mut b, (wrapper_hash, inner_hash)| {
+                b.push_bind(wrapper_hash)
+                    .push_bind(inner_hash)
+                    .push_bind(block_id);
+            })
+            .build()
+            .execute(&mut *sqlx_tx)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Save the serialized decrypted transactions of a block, it is up to
+    /// the caller to call sqlx_tx.commit().await?; for the changes to take
+    /// place in database.
+    #[instrument(skip_all)]
+    async fn save_raw_txs<'a>(
+        block_id: &[u8],
+        raw_txs: Vec<(Vec<u8>, i32, Vec<u8>)>,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        if raw_txs.is_empty() {
+            return Ok(());
+        }
+
+        let mut query_builder: QueryBuilder<_> = QueryBuilder::new(format!(
+            "INSERT INTO {}.{TX_RAW_TABLE_NAME}(
+                    hash,
+                    block_id,
+                    tx_index,
+                    raw
+            )",
+            network
+        ));
+
+        query_builder
+            .push_values(raw_txs.into_iter(), |mut b, (hash, tx_index, raw)| {
+                b.push_bind(hash)
+                    .push_bind(block_id)
+                    .push_bind(tx_index)
+                    .push_bind(raw);
+            })
+            .build()
+            .execute(&mut *sqlx_tx)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Returns the epoch of the wrappers of the decrypted transactions
+    /// identified by `hashes`, by decrypted transaction hash.
+    #[instrument(skip_all)]
+    async fn wrapper_epochs<'a>(
+        hashes: &[Vec<u8>],
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<HashMap<Vec<u8>, u64>, Error> {
+        let str = format!(
+            "SELECT x.inner_hash, w.epoch
+            FROM {0}.{TX_WRAPPERS_TABLE_NAME} x
+            JOIN {0}.{TX_TABLE_NAME} w ON w.hash = x.wrapper_hash
+            WHERE x.inner_hash = ANY($1) AND w.epoch IS NOT NULL",
+            network
+        );
+
+        let rows = query(&str).bind(hashes).fetch_all(&mut *sqlx_tx).await?;
+
+        let mut epochs = HashMap::new();
+        for row in rows {
+            let hash: Vec<u8> = row.try_get("inner_hash")?;
+            let epoch: i64 = row.try_get("epoch")?;
+            epochs.insert(hash, epoch as u64);
+        }
+
+        Ok(epochs)
+    }
+
+    /// Sets the epoch of the decrypted transactions of the wrappers of
+    /// `block_id` saved before them, like the first blocks indexed live
+    /// while the blocks below are backfilled, the epoch of a decrypted
+    /// transaction being the one of its wrapper. It is up to the caller to
+    /// call sqlx_tx.commit().await?; for the changes to take place in
+    /// database.
+    #[instrument(skip_all)]
+    async fn link_decrypted_txs<'a>(
+        block_id: &[u8],
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        let str = format!(
+            "SELECT t.hash, t.block_id, w.epoch
+            FROM {0}.{TX_WRAPPERS_TABLE_NAME} x
+            JOIN {0}.{TX_TABLE_NAME} w ON w.hash = x.wrapper_hash AND w.block_id = x.block_id
+            JOIN {0}.{TX_TABLE_NAME} t ON t.hash = x.inner_hash AND t.tx_type = 'Decrypted'
+            WHERE x.block_id = $1 AND w.epoch IS NOT NULL",
+            network
+        );
+
+        let rows = query(&str).bind(block_id).fetch_all(&mut *sqlx_tx).await?;
+
+        // whether a consensus key got its epoch, and so its pipeline epoch
+        let mut keys_linked = false;
+
+        for row in rows {
+            let hash: Vec<u8> = row.try_get("hash")?;
+            let inner_block_id: Vec<u8> = row.try_get("block_id")?;
+            let epoch: i64 = row.try_get("epoch")?;
+
+            for table in TX_EPOCH_TABLES {
+                let str = format!(
+                    "UPDATE {network}.{table} SET epoch = $1
+                    WHERE tx_hash = $2 AND block_id = $3 AND epoch IS NULL"
+                );
+
+                let res = query(&str)
+                    .bind(epoch)
+                    .bind(&hash)
+                    .bind(&inner_block_id)
+                    .execute(&mut *sqlx_tx)
+                    .await?;
+
+                if table == VALIDATOR_CONSENSUS_KEYS_TABLE_NAME && res.rows_affected() > 0 {
+                    keys_linked = true;
+                }
+            }
+
+            Self::update_withdrawable_epoch(&hash, &inner_block_id, sqlx_tx, network).await?;
+
+            // a withdrawal saved without its epoch did not withdraw anything
+            let str = format!(
+                "UPDATE {network}.{WITHDRAWALS_TABLE_NAME} SET epoch = $1
+                WHERE tx_hash = $2 AND block_id = $3 AND epoch IS NULL
+                RETURNING height, validator, COALESCE(source, validator) AS delegator"
+            );
+
+            let withdrawal = query(&str)
+                .bind(epoch)
+                .bind(&hash)
+                .bind(&inner_block_id)
+                .fetch_optional(&mut *sqlx_tx)
+                .await?;
+
+            if let Some(row) = withdrawal {
+                let height: i32 = row.try_get("height")?;
+                let validator: String = row.try_get("validator")?;
+                let delegator: String = row.try_get("delegator")?;
+
+                Self::save_unbond_withdrawals(
+                    &hash,
+                    &inner_block_id,
+                    height as u64,
+                    Some(epoch as u64),
+                    &delegator,
+                    &validator,
+                    sqlx_tx,
+                    network,
+                )
+                .await?;
+            }
+        }
+
+        if keys_linked {
+            Self::refresh_validator_tm_addresses(sqlx_tx, network).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Removes the rows of `table` saved for the transaction `tx`, as it
+    /// might have been decoded before.
+    async fn delete_tx_rows<'a>(
+        table: &str,
+        tx: &DecodedTx<'_>,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        let str = format!(
+            "DELETE FROM {}.{table} WHERE tx_hash = $1 AND block_id = $2",
+            network
+        );
+
+        query(&str)
+            .bind(tx.hash)
+            .bind(tx.block_id)
+            .execute(&mut *sqlx_tx)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Save the data of a tx_transfer transaction, it is up to the caller
+    /// to call sqlx_tx.commit().await?; for the changes to take place in
+    /// database.
+    #[instrument(skip_all)]
+    pub(crate) async fn save_transfer<'a>(
+        tx: &DecodedTx<'_>,
+        transfer: &token::Transfer,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        Self::delete_tx_rows(TRANSFERS_TABLE_NAME, tx, sqlx_tx, network).await?;
+
+        let str = format!(
+            "INSERT INTO {}.{TRANSFERS_TABLE_NAME}(
+                    block_id,
+                    height,
+                    epoch,
+                    tx_hash,
+                    source,
+                    target,
+                    token,
+                    amount,
+                    key,
+                    shielded
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8::NUMERIC, $9, $10)",
+            network
+        );
+
+        query(&str)
+            .bind(tx.block_id)
+            .bind(tx.height as i32)
+            .bind(tx.epoch.map(|e| e as i64))
+            .bind(tx.hash)
+            .bind(transfer.source.to_string())
+            .bind(transfer.target.to_string())
+            .bind(transfer.token.to_string())
+            .bind(transfer.amount.to_string_precise())
+            .bind(transfer.key.clone())
+            .bind(transfer.shielded.as_ref().map(|h| h.0.to_vec()))
+            .execute(&mut *sqlx_tx)
+            .await?;
+
+        // the amount moves from the source to the target
+        let amount = utils::to_base_units(&transfer.amount.to_string_precise());
+        let token = transfer.token.to_string();
+        let changes = vec![
+            (
+                transfer.source.to_string(),
+                token.clone(),
+                format!("-{amount}"),
+                "transfer",
+            ),
+            (transfer.target.to_string(), token, amount, "transfer"),
+        ];
+
+        Self::save_balance_changes(tx, changes, sqlx_tx, network).await
+    }
+
+    /// Sets the epoch the tokens of the unbond `tx_hash` can be withdrawn at
+    /// from the proof of stake parameters of the network, it is up to the
+    /// caller to call sqlx_tx.commit().await?; for the changes to take place
+    /// in database.
+    #[instrument(skip_all)]
+    async fn update_withdrawable_epoch<'a>(
+        tx_hash: &[u8],
+        block_id: &[u8],
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        let str = format!(
+            "UPDATE {0}.{UNBONDS_TABLE_NAME} u
+            SET withdrawable_epoch = {WITHDRAWABLE_EPOCH}
+            FROM {0}.{POS_PARAMETERS_TABLE_NAME} p
+            WHERE u.tx_hash = $1 AND u.block_id = $2",
+            network
+        );
+
+        query(&str)
+            .bind(tx_hash)
+            .bind(block_id)
+            .execute(&mut *sqlx_tx)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Computes the rows of the `validator_tm_addresses` table again, from
+    /// the consensus keys saved so far. It is up to the caller to call
+    /// sqlx_tx.commit().await?; for the changes to take place in database.
+    #[instrument(skip_all)]
+    async fn refresh_validator_tm_addresses<'a>(
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        query(&format!(
+            "DELETE FROM {network}.{VALIDATOR_TM_ADDRESSES_TABLE_NAME}"
+        ))
+        .execute(&mut *sqlx_tx)
+        .await?;
+
+        query(&get_refresh_validator_tm_addresses_query(network))
+            .execute(&mut *sqlx_tx)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Refreshes the `validator_tm_addresses` table if the block `block_id`
+    /// changes it: a consensus key is saved with it, or its wrappers, of the
+    /// epoch `block_epoch` at most, reach the pipeline epoch of a key not used
+    /// yet or used from a greater height, as blocks are not always saved in
+    /// order. It is up to the caller to call sqlx_tx.commit().await?; for the
+    /// changes to take place in database.
+    #[instrument(skip(block_id, sqlx_tx, network))]
+    async fn update_validator_tm_addresses<'a>(
+        block_id: &[u8],
+        height: u64,
+        block_epoch: Option<u64>,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        let str = format!(
+            "SELECT EXISTS (
+                SELECT 1 FROM {0}.{VALIDATOR_CONSENSUS_KEYS_TABLE_NAME} WHERE block_id = $1
+            ) OR EXISTS (
+                SELECT 1 FROM {0}.{VALIDATOR_TM_ADDRESSES_TABLE_NAME}
+                WHERE pipeline_epoch <= $3
+                AND (start_height IS NULL OR start_height > $2)
+            ) AS refresh",
+            network
+        );
+
+        let refresh: bool = query(&str)
+            .bind(block_id)
+            .bind(height as i32)
+            .bind(block_epoch.map(|e| e as i64))
+            .fetch_one(&mut *sqlx_tx)
+            .await?
+            .try_get("refresh")?;
+
+        if refresh {
+            Self::refresh_validator_tm_addresses(sqlx_tx, network).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Save the changes of the balances caused by a transaction, as tuples
+    /// (address, token, signed amount in the smallest unit, kind). It is up
+    /// to the caller to call sqlx_tx.commit().await?; for the changes to
+    /// take place in database.
+    async fn save_balance_changes<'a>(
+        tx: &DecodedTx<'_>,
+        changes: Vec<(String, String, String, &'static str)>,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        Self::delete_tx_rows(BALANCE_CHANGES_TABLE_NAME, tx, sqlx_tx, network).await?;
+
+        if changes.is_empty() {
+            return Ok(());
+        }
+
+        let mut query_builder: QueryBuilder<_> = QueryBuilder::new(format!(
+            "INSERT INTO {}.{BALANCE_CHANGES_TABLE_NAME}(
+                    block_id,
+                    height,
+                    tx_hash,
+                    address,
+                    token,
+                    amount,
+                    kind
+                )",
+            network
+        ));
+
+        query_builder
+            .push_values(
+                changes.into_iter(),
+                |mut b, (address, token, amount, kind)| {
+                    b.push_bind(tx.block_id)
+                        .push_bind(tx.height as i32)
+                        .push_bind(tx.hash)
+                        .push_bind(address)
+                        .push_bind(token)
+                        .push_bind(amount)
+                        .push_unseparated("::NUMERIC")
+                        .push_bind(kind);
+                },
+            )
+            .build()
+            .execute(&mut *sqlx_tx)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Save the data of a tx_bond or tx_unbond transaction to `table`, it
+    /// is up to the caller to call sqlx_tx.commit().await?; for the changes
+    /// to take place in database.
+    #[instrument(skip(tx, bond, sqlx_tx, network))]
+    pub(crate) async fn save_bond<'a>(
+        table: &str,
+        tx: &DecodedTx<'_>,
+        bond: &Bond,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        Self::delete_tx_rows(table, tx, sqlx_tx, network).await?;
+
+        let str = format!(
+            "INSERT INTO {}.{table}(
+                    block_id,
+                    height,
+                    epoch,
+                    tx_hash,
+                    validator,
+                    source,
+                    amount
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7::NUMERIC)",
+            network
+        );
+
+        query(&str)
+            .bind(tx.block_id)
+            .bind(tx.height as i32)
+            .bind(tx.epoch.map(|e| e as i64))
+            .bind(tx.hash)
+            .bind(bond.validator.to_string())
+            .bind(bond.source.as_ref().map(|s| s.to_string()))
+            .bind(bond.amount.to_string_native())
+            .execute(&mut *sqlx_tx)
+            .await?;
+
+        // unbonds also record the epoch their tokens can be withdrawn at
+        if table == UNBONDS_TABLE_NAME {
+            Self::update_withdrawable_epoch(tx.hash, tx.block_id, sqlx_tx, network).await?;
+        }
+
+        // without a source the validator bonds its own tokens
+        let delegator = bond.source.as_ref().unwrap_or(&bond.validator).to_string();
+        let validator = bond.validator.to_string();
+        let amount = bond.amount.to_string_native();
+
+        // bonded tokens leave the balance of the delegator, unbonded ones
+        // only come back once withdrawn.
+        let balance_changes = if table == UNBONDS_TABLE_NAME {
+            vec![]
+        } else {
+            let base_units = utils::to_base_units(&amount);
+            vec![(
+                delegator.clone(),
+                NATIVE_TOKEN_ADDR.to_string(),
+                format!("-{base_units}"),
+                "bond",
+            )]
+        };
+        Self::save_balance_changes(tx, balance_changes, sqlx_tx, network).await?;
+
+        let changes = if table == UNBONDS_TABLE_NAME {
+            vec![(delegator, validator, format!("-{amount}"), "unbond")]
+        } else {
+            vec![(delegator, validator, amount, "bond")]
+        };
+
+        Self::save_bond_changes(tx, changes, sqlx_tx, network).await
+    }
+
+    /// Save the changes of the amounts bonded by a transaction, as tuples
+    /// (delegator, validator, signed amount, kind). It is up to the caller
+    /// to call sqlx_tx.commit().await?; for the changes to take place in
+    /// database.
+    async fn save_bond_changes<'a>(
+        tx: &DecodedTx<'_>,
+        changes: Vec<(String, String, String, &'static str)>,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        Self::delete_tx_rows(BOND_CHANGES_TABLE_NAME, tx, sqlx_tx, network).await?;
+
+        let mut query_builder: QueryBuilder<_> = QueryBuilder::new(format!(
+            "INSERT INTO {}.{BOND_CHANGES_TABLE_NAME}(
+                    block_id,
+                    height,
+                    epoch,
+                    tx_hash,
+                    delegator,
+                    validator,
+                    amount,
+                    kind
+                )",
+            network
+        ));
+
+        query_builder
+            .push_values(
+                changes.into_iter(),
+                |mut b, (delegator, validator, amount, kind)| {
+                    b.push_bind(tx.block_id)
+                        .push_bind(tx.height as i32)
+                        .push_bind(tx.epoch.map(|e| e as i64))
+                        .push_bind(tx.hash)
+                        .push_bind(delegator)
+                        .push_bind(validator)
+                        .push_bind(amount)
+                        .push_unseparated("::NUMERIC")
+                        .push_bind(kind);
+                },
+            )
+            .build()
+            .execute(&mut *sqlx_tx)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Save the data of a tx_redelegate transaction, it is up to the caller
+    /// to call sqlx_tx.commit().await?; for the changes to take place in
+    /// database.
+    #[instrument(skip_all)]
+    pub(crate) async fn save_redelegation<'a>(
+        tx: &DecodedTx<'_>,
+        redelegation: &Redelegation,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        Self::delete_tx_rows(REDELEGATIONS_TABLE_NAME, tx, sqlx_tx, network).await?;
+
+        let str = format!(
+            "INSERT INTO {}.{REDELEGATIONS_TABLE_NAME}(
+                    block_id,
+                    height,
+                    epoch,
+                    tx_hash,
+                    src_validator,
+                    dest_validator,
+                    owner,
+                    amount
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8::NUMERIC)",
+            network
+        );
+
+        query(&str)
+            .bind(tx.block_id)
+            .bind(tx.height as i32)
+            .bind(tx.epoch.map(|e| e as i64))
+            .bind(tx.hash)
+            .bind(redelegation.src_validator.to_string())
+            .bind(redelegation.dest_validator.to_string())
+            .bind(redelegation.owner.to_string())
+            .bind(redelegation.amount.to_string_native())
+            .execute(&mut *sqlx_tx)
+            .await?;
+
+        // the bond moves from the source validator to the destination one
+        let owner = redelegation.owner.to_string();
+        let amount = redelegation.amount.to_string_native();
+        let changes = vec![
+            (
+                owner.clone(),
+                redelegation.src_validator.to_string(),
+                format!("-{amount}"),
+                "redelegation",
+            ),
+            (
+                owner,
+                redelegation.dest_validator.to_string(),
+                amount,
+                "redelegation",
+            ),
+        ];
+
+        Self::save_bond_changes(tx, changes, sqlx_tx, network).await
+    }
+
+    /// Save the data of a tx_withdraw transaction, it is up to the caller
+    /// to call sqlx_tx.commit().await?; for the changes to take place in
+    /// database.
+    #[instrument(skip_all)]
+    pub(crate) async fn save_withdrawal<'a>(
+        tx: &DecodedTx<'_>,
+        withdraw: &Withdraw,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        Self::delete_tx_rows(WITHDRAWALS_TABLE_NAME, tx, sqlx_tx, network).await?;
+
+        let str = format!(
+            "INSERT INTO {}.{WITHDRAWALS_TABLE_NAME}(
+                    block_id,
+                    height,
+                    epoch,
+                    tx_hash,
+                    validator,
+                    source
+            ) VALUES ($1, $2, $3, $4, $5, $6)",
+            network
+        );
+
+        query(&str)
+            .bind(tx.block_id)
+            .bind(tx.height as i32)
+            .bind(tx.epoch.map(|e| e as i64))
+            .bind(tx.hash)
+            .bind(withdraw.validator.to_string())
+            .bind(withdraw.source.as_ref().map(|s| s.to_string()))
+            .execute(&mut *sqlx_tx)
+            .await?;
+
+        let delegator = withdraw.source.as_ref().unwrap_or(&withdraw.validator);
+
+        Self::delete_tx_rows(UNBOND_WITHDRAWALS_TABLE_NAME, tx, sqlx_tx, network).await?;
+        Self::delete_tx_rows(BALANCE_CHANGES_TABLE_NAME, tx, sqlx_tx, network).await?;
+
+        Self::save_unbond_withdrawals(
+            tx.hash,
+            tx.block_id,
+            tx.height,
+            tx.epoch,
+            &delegator.to_string(),
+            &withdraw.validator.to_string(),
+            sqlx_tx,
+            network,
+        )
+        .await
+    }
+
+    /// Save the unbonds withdrawn by the withdrawal `tx_hash` and credits
+    /// them back to the delegator. It is up to the caller to call
+    /// sqlx_tx.commit().await?; for the changes to take place in database.
+    #[allow(clippy::too_many_arguments)]
+    #[instrument(skip_all)]
+    async fn save_unbond_withdrawals<'a>(
+        tx_hash: &[u8],
+        block_id: &[u8],
+        height: u64,
+        epoch: Option<u64>,
+        delegator: &str,
+        validator: &str,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        // The amount withdrawn is not known from the transaction, it is
+        // assumed to withdraw all the unbonds of the pair withdrawable at
+        // the epoch of the transaction and not withdrawn yet. Without the
+        // epoch of the transaction nothing is withdrawn.
+        let str = format!(
+            "INSERT INTO {0}.{UNBOND_WITHDRAWALS_TABLE_NAME}(
+                    block_id,
+                    height,
+                    tx_hash,
+                    unbond_block_id,
+                    unbond_tx_hash,
+                    delegator,
+                    validator,
+                    amount
+            )
+            SELECT $1, $2, $3, u.block_id, u.tx_hash, $4, u.validator, u.amount
+            FROM {0}.{UNBONDS_TABLE_NAME} u
+            WHERE u.validator = $5
+            AND COALESCE(u.source, u.validator) = $4
+            AND u.withdrawable_epoch <= $6
+            AND NOT EXISTS (
+                SELECT 1 FROM {0}.{UNBOND_WITHDRAWALS_TABLE_NAME} w
+                WHERE w.unbond_tx_hash = u.tx_hash AND w.unbond_block_id = u.block_id
+            )",
+            network
+        );
+
+        query(&str)
+            .bind(block_id)
+            .bind(height as i32)
+            .bind(tx_hash)
+            .bind(delegator)
+            .bind(validator)
+            .bind(epoch.map(|e| e as i64))
+            .execute(&mut *sqlx_tx)
+            .await?;
+
+        // the unbonds withdrawn are credited back to the delegator
+        let str = format!(
+            "INSERT INTO {0}.{BALANCE_CHANGES_TABLE_NAME}(
+                    block_id,
+                    height,
+                    tx_hash,
+                    address,
+                    token,
+                    amount,
+                    kind
+            )
+            SELECT $1, $2, $3, $4, $5, TRUNC(SUM(w.amount) * POWER(10::NUMERIC, $6)), 'withdraw'
+            FROM {0}.{UNBOND_WITHDRAWALS_TABLE_NAME} w
+            WHERE w.tx_hash = $3 AND w.block_id = $1
+            HAVING COUNT(*) > 0",
+            network
+        );
+
+        query(&str)
+            .bind(block_id)
+            .bind(height as i32)
+            .bind(tx_hash)
+            .bind(delegator)
+            .bind(NATIVE_TOKEN_ADDR)
+            .bind(NATIVE_TOKEN_DECIMALS as i32)
+            .execute(&mut *sqlx_tx)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Save the data of a tx_vote_proposal transaction, it is up to the
+    /// caller to call sqlx_tx.commit().await?; for the changes to take place
+    /// in database.
+    #[instrument(skip_all)]
+    pub(crate) async fn save_vote<'a>(
+        tx: &DecodedTx<'_>,
+        vote: &VoteProposalData,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        Self::delete_tx_rows(VOTES_TABLE_NAME, tx, sqlx_tx, network).await?;
+
+        let str = format!(
+            "INSERT INTO {}.{VOTES_TABLE_NAME}(
+                    block_id,
+                    height,
+                    epoch,
+                    tx_hash,
+                    proposal_id,
+                    vote,
+                    voter
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7)",
+            network
+        );
+
+        query(&str)
+            .bind(tx.block_id)
+            .bind(tx.height as i32)
+            .bind(tx.epoch.map(|e| e as i64))
+            .bind(tx.hash)
+            // WARNING! proposal ids are u64 but are not expected to go higher than i64 max value
+            .bind(vote.id as i64)
+            // yay, nay or abstain
+            .bind(format!("{:?}", vote.vote).to_lowercase())
+            .bind(vote.voter.to_string())
+            .execute(&mut *sqlx_tx)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Returns the results of the execution of a transaction, as found in
+    /// the `inner_tx` and `log` attributes of its events. The events of a
+    /// block are saved before its transactions.
+    pub(crate) async fn tx_results<'a>(
+        tx: &DecodedTx<'_>,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<Vec<String>, Error> {
+        let str = format!(
+            "SELECT a.value
+            FROM {}.{EVENTS_TABLE_NAME} e, jsonb_each_text(e.attributes) a
+            WHERE e.block_id = $1 AND e.tx_hash = $2
+            AND a.key IN ('inner_tx', 'log')",
+            network
+        );
+
+        let rows = query(&str)
+            .bind(tx.block_id)
+            .bind(tx.hash)
+            .fetch_all(&mut *sqlx_tx)
+            .await?;
+
+        rows.iter()
+            .map(|row| row.try_get("value").map_err(Error::from))
+            .collect()
+    }
+
+    /// Save the data of a tx_init_proposal transaction along with the content
+    /// of the proposal, it is up to the caller to call sqlx_tx.commit().await?;
+    /// for the changes to take place in database.
+    #[instrument(skip_all)]
+    pub(crate) async fn save_proposal<'a>(
+        tx: &DecodedTx<'_>,
+        id: u64,
+        proposal: &InitProposalData,
+        content: Option<BTreeMap<String, String>>,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        Self::delete_tx_rows(PROPOSALS_TABLE_NAME, tx, sqlx_tx, network).await?;
+
+        // e.g "Default" or {"PGFSteward": [...]}
+        let type_data = serde_json::to_value(&proposal.r#type)?;
+        let proposal_type = match &type_data {
+            serde_json::Value::String(name) => name.clone(),
+            serde_json::Value::Object(o) => o.keys().next().cloned().unwrap_or_default(),
+            _ => String::new(),
+        };
+        let content = content.map(serde_json::to_value).transpose()?;
+
+        let str = format!(
+            "INSERT INTO {}.{PROPOSALS_TABLE_NAME}(
+                    block_id,
+                    height,
+                    epoch,
+                    tx_hash,
+                    proposal_id,
+                    author,
+                    proposal_type,
+                    type_data,
+                    voting_start_epoch,
+                    voting_end_epoch,
+                    grace_epoch,
+                    content
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
+            network
+        );
+
+        query(&str)
+            .bind(tx.block_id)
+            .bind(tx.height as i32)
+            .bind(tx.epoch.map(|e| e as i64))
+            .bind(tx.hash)
+            // WARNING! proposal ids are u64 but are not expected to go higher than i64 max value
+            .bind(id as i64)
+            .bind(proposal.author.to_string())
+            .bind(proposal_type)
+            .bind(type_data)
+            .bind(proposal.voting_start_epoch.0 as i64)
+            .bind(proposal.voting_end_epoch.0 as i64)
+            .bind(proposal.grace_epoch.0 as i64)
+            .bind(content)
+            .execute(&mut *sqlx_tx)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Save a change of the state of a validator, `action` being the type of
+    /// the transaction without its `tx_` prefix, e.g `become_validator`. It
+    /// is up to the caller to call sqlx_tx.commit().await?; for the changes
+    /// to take place in database.
+    #[instrument(skip(tx, sqlx_tx, network))]
+    pub(crate) async fn save_validator_state<'a>(
+        tx: &DecodedTx<'_>,
+        validator: &str,
+        action: &str,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        Self::delete_tx_rows(VALIDATOR_STATES_TABLE_NAME, tx, sqlx_tx, network).await?;
+
+        let str = format!(
+            "INSERT INTO {}.{VALIDATOR_STATES_TABLE_NAME}(
+                    block_id,
+                    height,
+                    epoch,
+                    tx_hash,
+                    validator,
+                    action
+            ) VALUES ($1, $2, $3, $4, $5, $6)",
+            network
+        );
+
+        query(&str)
+            .bind(tx.block_id)
+            .bind(tx.height as i32)
+            .bind(tx.epoch.map(|e| e as i64))
+            .bind(tx.hash)
+            .bind(validator)
+            .bind(action)
+            .execute(&mut *sqlx_tx)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Save the commission rate of a validator, set when it becomes a
+    /// validator or changed later on. It is up to the caller to call
+    /// sqlx_tx.commit().await?; for the changes to take place in database.
+    #[instrument(skip(tx, sqlx_tx, network))]
+    pub(crate) async fn save_validator_commission<'a>(
+        tx: &DecodedTx<'_>,
+        validator: &str,
+        commission_rate: &str,
+        max_commission_rate_change: Option<&str>,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        Self::delete_tx_rows(VALIDATOR_COMMISSIONS_TABLE_NAME, tx, sqlx_tx, network).await?;
+
+        let str = format!(
+            "INSERT INTO {}.{VALIDATOR_COMMISSIONS_TABLE_NAME}(
+                    block_id,
+                    height,
+                    epoch,
+                    tx_hash,
+                    validator,
+                    commission_rate,
+                    max_commission_rate_change
+            ) VALUES ($1, $2, $3, $4, $5, $6::NUMERIC, $7::NUMERIC)",
+            network
+        );
+
+        query(&str)
+            .bind(tx.block_id)
+            .bind(tx.height as i32)
+            .bind(tx.epoch.map(|e| e as i64))
+            .bind(tx.hash)
+            .bind(validator)
+            .bind(commission_rate)
+            .bind(max_commission_rate_change)
+            .execute(&mut *sqlx_tx)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Save the metadata of a validator found in the decoded data of the
+    /// transaction, fields left empty are not changed. It is up to the caller
+    /// to call sqlx_tx.commit().await?; for the changes to take place in
+    /// database.
+    #[instrument(skip(tx, sqlx_tx, network))]
+    pub(crate) async fn save_validator_metadata<'a>(
+        tx: &DecodedTx<'_>,
+        validator: &str,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        Self::delete_tx_rows(VALIDATOR_METADATA_TABLE_NAME, tx, sqlx_tx, network).await?;
+
+        let field = |name: &str| tx.data.get(name).and_then(|v| v.as_str()).map(String::from);
+
+        let str = format!(
+            "INSERT INTO {}.{VALIDATOR_METADATA_TABLE_NAME}(
+                    block_id,
+                    height,
+                    epoch,
+                    tx_hash,
+                    validator,
+                    email,
+                    description,
+                    website,
+                    discord_handle,
+                    avatar
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+            network
+        );
+
+        query(&str)
+            .bind(tx.block_id)
+            .bind(tx.height as i32)
+            .bind(tx.epoch.map(|e| e as i64))
+            .bind(tx.hash)
+            .bind(validator)
+            .bind(field("email"))
+            .bind(field("description"))
+            .bind(field("website"))
+            .bind(field("discord_handle"))
+            .bind(field("avatar"))
+            .execute(&mut *sqlx_tx)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Save the consensus key of a validator, set when it becomes a
+    /// validator or changed later on. It is up to the caller to call
+    /// sqlx_tx.commit().await?; for the changes to take place in database.
+    #[instrument(skip(tx, consensus_key, sqlx_tx, network))]
+    pub(crate) async fn save_validator_consensus_key<'a>(
+        tx: &DecodedTx<'_>,
+        validator: &str,
+        consensus_key: &PublicKey,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        Self::delete_tx_rows(VALIDATOR_CONSENSUS_KEYS_TABLE_NAME, tx, sqlx_tx, network).await?;
+
+        let str = format!(
+            "INSERT INTO {}.{VALIDATOR_CONSENSUS_KEYS_TABLE_NAME}(
+                    block_id,
+                    height,
+                    epoch,
+                    tx_hash,
+                    validator,
+                    consensus_key,
+                    tm_address
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7)",
+            network
+        );
+
+        query(&str)
+            .bind(tx.block_id)
+            .bind(tx.height as i32)
+            .bind(tx.epoch.map(|e| e as i64))
+            .bind(tx.hash)
+            .bind(validator)
+            .bind(consensus_key.to_string())
+            .bind(tm_consensus_key_raw_hash(consensus_key))
+            .execute(&mut *sqlx_tx)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Save a fungible token transfer of an ibc transaction, it is up to the
+    /// caller to call sqlx_tx.commit().await?; for the changes to take place
+    /// in database.
+    #[instrument(skip_all)]
+    pub(crate) async fn save_ibc_transfer<'a>(
+        tx: &DecodedTx<'_>,
+        transfer: IbcTransfer,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        Self::delete_tx_rows(IBC_TRANSFERS_TABLE_NAME, tx, sqlx_tx, network).await?;
+
+        // only the Namada side of the transfer has a balance here, ibc
+        // amounts are already in the smallest unit.
+        let balance_change = if transfer.direction == "send" {
+            (
+                transfer.sender.clone(),
+                transfer.token.clone(),
+                format!("-{}", transfer.amount),
+                "ibc",
+            )
+        } else {
+            (
+                transfer.receiver.clone(),
+                transfer.token.clone(),
+                transfer.amount.clone(),
+                "ibc",
+            )
+        };
+
+        let str = format!(
+            "INSERT INTO {}.{IBC_TRANSFERS_TABLE_NAME}(
+                    block_id,
+                    height,
+                    tx_hash,
+                    direction,
+                    sender,
+                    receiver,
+                    denom,
+                    amount,
+                    port,
+                    channel,
+                    sequence
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8::NUMERIC, $9, $10, $11)",
+            network
+        );
+
+        query(&str)
+            .bind(tx.block_id)
+            .bind(tx.height as i32)
+            .bind(tx.hash)
+            .bind(transfer.direction)
+            .bind(transfer.sender)
+            .bind(transfer.receiver)
+            .bind(transfer.denom)
+            .bind(transfer.amount)
+            .bind(transfer.port)
+            .bind(transfer.channel)
+            // WARNING! sequences are u64 but are not expected to go higher than i64 max value
+            .bind(transfer.sequence.map(|s| s as i64))
+            .execute(&mut *sqlx_tx)
+            .await?;
+
+        Self::save_balance_changes(tx, vec![balance_change], sqlx_tx, network).await
+    }
+
+    /// Save the data of a tx_claim_rewards transaction, it is up to the
+    /// caller to call sqlx_tx.commit().await?; for the changes to take place
+    /// in database.
+    ///
+    /// The amount of rewards claimed is not part of the transaction, so the
+    /// claim is not recorded in the balance ledger.
+    #[instrument(skip_all)]
+    pub(crate) async fn save_claim_rewards<'a>(
+        tx: &DecodedTx<'_>,
+        claim: &Withdraw,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        Self::delete_tx_rows(REWARD_CLAIMS_TABLE_NAME, tx, sqlx_tx, network).await?;
+
+        let str = format!(
+            "INSERT INTO {}.{REWARD_CLAIMS_TABLE_NAME}(
+                    block_id,
+                    height,
+                    epoch,
+                    tx_hash,
+                    validator,
+                    source
+            ) VALUES ($1, $2, $3, $4, $5, $6)",
+            network
+        );
+
+        query(&str)
+            .bind(tx.block_id)
+            .bind(tx.height as i32)
+            .bind(tx.epoch.map(|e| e as i64))
+            .bind(tx.hash)
+            .bind(claim.validator.to_string())
+            .bind(claim.source.as_ref().map(|s| s.to_string()))
+            .execute(&mut *sqlx_tx)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Save all the begin block, end block, finalize block and tx result
+    /// events of the block results, it is up to the caller to call
+    /// sqlx_tx.commit().await?; for the changes to take place in database.
+    ///
+    /// Events with a `hash` attribute are linked to the transaction
+    /// identified by it.
+    #[instrument(skip(block_id, block_results, sqlx_tx, network))]
+    async fn save_events<'a>(
+        block_id: &[u8],
+        block_height: u64,
+        block_results: &block_results::Response,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        debug!("saving events");
+
+        let mut events: Vec<EventRow> = Vec::new();
+
+        let mut push_events = |source: &'static str, tx_index: Option<i32>, list: &[Event]| {
+            for (event_index, event) in list.iter().enumerate() {
+                let mut attributes = serde_json::Map::new();
+                let mut tx_hash = None;
+
+                for attr in event.attributes.iter() {
+                    if attr.key == "hash" {
+                        tx_hash = hex::decode(attr.value.to_ascii_lowercase()).ok();
+                    }
+                    attributes.insert(attr.key.clone(), json!(attr.value));
+                }
+
+                events.push((
+                    source,
+                    event_index as i32,
+                    tx_index,
+                    tx_hash,
+                    event.kind.clone(),
+                    serde_json::Value::Object(attributes),
+                ));
+            }
+        };
+
+        if let Some(list) = &block_results.begin_block_events {
+            push_events("begin_block", None, list);
+        }
+
+        for (tx_index, result) in block_results.txs_results.iter().flatten().enumerate() {
+            push_events("tx_result", Some(tx_index as i32), &result.events);
+        }
+
+        if let Some(list) = &block_results.end_block_events {
+            push_events("end_block", None, list);
+        }
+
+        push_events("finalize_block", None, &block_results.finalize_block_events);
+
+        while !events.is_empty() {
+            let batch: Vec<EventRow> = events
+                .drain(..events.len().min(EVENTS_BATCH_SIZE))
+                .collect();
+
+            let mut query_builder: QueryBuilder<_> = QueryBuilder::new(format!(
+                "INSERT INTO {}.{EVENTS_TABLE_NAME}(
+                    block_id,
+                    height,
+                    source,
+                    event_index,
+                    tx_index,
+                    tx_hash,
+                    event_type,
+                    attributes
+                )",
+                network
+            ));
+
+            query_builder
+                .push_values(
+                    batch.into_iter(),
+                    |mut b, (source, event_index, tx_index, tx_hash, event_type, attributes)| {
+                        b.push_bind(block_id)
+                            .push_bind(block_height as i32)
+                            .push_bind(source)
+                            .push_bind(event_index)
+                            .push_bind(tx_index)
+                            .push_bind(tx_hash)
+                            .push_bind(event_type)
+                            .push_bind(attributes);
+                    },
+                )
+                .build()
+                .execute(&mut *sqlx_tx)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Save the transactions that could not be decoded, it is up to the
+    /// caller to call sqlx_tx.commit().await?; for the changes to take
+    /// place in database.
+    #[instrument(skip_all)]
+    async fn save_decode_failures<'a>(
+        block_id: &[u8],
+        failures: Vec<DecodeFailure>,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        if failures.is_empty() {
+            return Ok(());
+        }
+
+        let mut query_builder: QueryBuilder<_> = QueryBuilder::new(format!(
+            "INSERT INTO {}.{DECODE_FAILURES_TABLE_NAME}(
+                    hash,
+                    block_id,
+                    tx_type,
+                    code,
+                    data,
+                    error
+            )",
+            network
+        ));
+
+        query_builder
+            .push_values(
+                failures.into_iter(),
+                |mut b, (hash, tx_type, code, data, error)| {
+                    b.push_bind(hash)
+                        .push_bind(block_id)
+                        .push_bind(tx_type)
+                        .push_bind(code)
+                        .push_bind(data)
+                        .push_bind(error);
+                },
+            )
+            .build()
+            .execute(&mut *sqlx_tx)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn create_indexes(&self) -> Result<(), Error> {
+        // we create indexes on the tables to facilitate querying data
+        query(
+            format!(
+                "
+                ALTER TABLE {}.blocks ADD CONSTRAINT pk_block_id PRIMARY KEY (block_id);
+            ",
+                self.network
+            )
+            .as_str(),
+        )
+        .execute(&*self.pool)
+        .await?;
+
+        query(
+            format!(
+                "CREATE UNIQUE INDEX ux_header_height ON {}.blocks (header_height);",
+                self.network
+            )
+            .as_str(),
+        )
+        .execute(&*self.pool)
+        .await?;
+
+        // If a failed transaction is resent and successfull we don't have a unique private key in the tx hash...
+        // query(
+        //     format!(
+        //         "ALTER TABLE {}.transactions ADD CONSTRAINT pk_hash PRIMARY KEY (hash);",
+        //         self.network
+        //     )
+        //     .as_str(),
+        // )
+        // .execute(&*self.pool)
+        // .await?;
+
+        query(format!("ALTER TABLE {0}.transactions ADD CONSTRAINT fk_block_id FOREIGN KEY (block_id) REFERENCES {0}.blocks (block_id);", self.network).as_str())
+            .execute(&*self.pool)
+            .await?;
+
+        query(
+            format!(
+                "CREATE INDEX x_wrapper_hash_tx_wrappers ON {}.tx_wrappers USING HASH (wrapper_hash);",
+                self.network
+            )
+            .as_str(),
+        )
+        .execute(&*self.pool)
+        .await?;
+
+        query(
+            format!(
+                "CREATE INDEX x_inner_hash_tx_wrappers ON {}.tx_wrappers USING HASH (inner_hash);",
+                self.network
+            )
+            .as_str(),
+        )
+        .execute(&*self.pool)
+        .await?;
+
+        query(
+            format!(
+                "CREATE INDEX x_hash_tx_raw ON {}.tx_raw USING HASH (hash);",
+                self.network
+            )
+            .as_str(),
+        )
+        .execute(&*self.pool)
+        .await?;
+
+        query(
+            format!(
+                "CREATE INDEX x_sender_ibc_transfers ON {}.ibc_transfers USING HASH (sender);",
+                self.network
+            )
+            .as_str(),
+        )
+        .execute(&*self.pool)
+        .await?;
+
+        query(
+            format!(
+                "CREATE INDEX x_receiver_ibc_transfers ON {}.ibc_transfers USING HASH (receiver);",
+                self.network
+            )
+            .as_str(),
+        )
+        .execute(&*self.pool)
+        .await?;
+
+        query(
+            format!(
+                "CREATE INDEX x_source_transfers ON {}.transfers USING HASH (source);",
+                self.network
+            )
+            .as_str(),
+        )
+        .execute(&*self.pool)
+        .await?;
+
+        query(
+            format!(
+                "CREATE INDEX x_target_transfers ON {}.transfers USING HASH (target);",
+                self.network
+            )
+            .as_str(),
+        )
+        .execute(&*self.pool)
+        .await?;
+
+        query(
+            format!(
+                "CREATE INDEX x_source_bonds ON {}.bonds USING HASH (source);",
+                self.network
+            )
+            .as_str(),
+        )
+        .execute(&*self.pool)
+        .await?;
+
+        query(
+            format!(
+                "CREATE INDEX x_source_unbonds ON {}.unbonds USING HASH (source);",
+                self.network
+            )
+            .as_str(),
+        )
+        .execute(&*self.pool)
+        .await?;
+
+        query(
+            format!(
+                "CREATE INDEX x_owner_redelegations ON {}.redelegations USING HASH (owner);",
+                self.network
+            )
+            .as_str(),
+        )
+        .execute(&*self.pool)
+        .await?;
+
+        query(
+            format!(
+                "CREATE INDEX x_source_withdrawals ON {}.withdrawals USING HASH (source);",
+                self.network
+            )
+            .as_str(),
+        )
+        .execute(&*self.pool)
+        .await?;
+
+        query(
+            format!(
+                "CREATE INDEX x_source_reward_claims ON {}.reward_claims USING HASH (source);",
+                self.network
+            )
+            .as_str(),
+        )
+        .execute(&*self.pool)
+        .await?;
+
+        query(
+            format!(
+                "CREATE INDEX x_proposal_id_votes ON {}.votes USING HASH (proposal_id);",
+                self.network
+            )
+            .as_str(),
+        )
+        .execute(&*self.pool)
+        .await?;
+
+        query(
+            format!(
+                "CREATE INDEX x_address_balance_changes ON {}.balance_changes USING HASH (address);",
+                self.network
+            )
+            .as_str(),
+        )
+        .execute(&*self.pool)
+        .await?;
+
+        query(
+            format!(
+                "CREATE INDEX x_delegator_bond_changes ON {}.bond_changes USING HASH (delegator);",
+                self.network
+            )
+            .as_str(),
+        )
+        .execute(&*self.pool)
+        .await?;
+
+        query(
+            format!(
+                "CREATE INDEX x_validator_bond_changes ON {}.bond_changes USING HASH (validator);",
+                self.network
+            )
+            .as_str(),
+        )
+        .execute(&*self.pool)
+        .await?;
+
+        query(
+            format!(
+                "CREATE INDEX x_unbond_tx_hash_unbond_withdrawals ON {}.unbond_withdrawals USING HASH (unbond_tx_hash);",
+                self.network
+            )
+            .as_str(),
+        )
+        .execute(&*self.pool)
+        .await?;
+
+        query(
+            format!(
+                "CREATE INDEX x_proposal_id_proposals ON {}.proposals USING HASH (proposal_id);",
+                self.network
+            )
+            .as_str(),
+        )
+        .execute(&*self.pool)
+        .await?;
+
+        query(
+            format!(
+                "CREATE INDEX x_validator_validator_states ON {}.validator_states USING HASH (validator);",
+                self.network
+            )
+            .as_str(),
+        )
+        .execute(&*self.pool)
+        .await?;
+
+        query(
+            format!(
+                "CREATE INDEX x_validator_validator_commissions ON {}.validator_commissions USING HASH (validator);",
+                self.network
+            )
+            .as_str(),
+        )
+        .execute(&*self.pool)
+        .await?;
+
+        query(
+            format!(
+                "CREATE INDEX x_validator_validator_metadata ON {}.validator_metadata USING HASH (validator);",
+                self.network
+            )
+            .as_str(),
+        )
+        .execute(&*self.pool)
+        .await?;
+
+        query(
+            format!(
+                "CREATE INDEX x_validator_validator_consensus_keys ON {}.validator_consensus_keys USING HASH (validator);",
+                self.network
+            )
+            .as_str(),
+        )
+        .execute(&*self.pool)
+        .await?;
+
+        query(
+            format!(
+                "CREATE INDEX x_tm_address_validator_consensus_keys ON {}.validator_consensus_keys USING HASH (tm_address);",
+                self.network
+            )
+            .as_str(),
+        )
+        .execute(&*self.pool)
+        .await?;
+
+        // to find the first height of the pipeline epoch of consensus keys
+        query(
+            format!(
+                "CREATE INDEX x_epoch_transactions ON {}.transactions (epoch);",
+                self.network
+            )
+            .as_str(),
+        )
+        .execute(&*self.pool)
+        .await?;
+
+        query(
+            format!(
+                "CREATE INDEX x_tm_address_validator_tm_addresses ON {}.validator_tm_addresses USING HASH (tm_address);",
+                self.network
+            )
+            .as_str(),
+        )
+        .execute(&*self.pool)
+        .await?;
+
+        query(
+            format!(
+                "CREATE INDEX x_validator_address_commit_signatures ON {}.commit_signatures USING HASH (validator_address);",
+                self.network
+            )
+            .as_str(),
+        )
+        .execute(&*self.pool)
+        .await?;
+
+        query(
+            format!(
+                "CREATE INDEX x_block_id_commit_signatures ON {}.commit_signatures USING HASH (block_id);",
+                self.network
+            )
+            .as_str(),
+        )
+        .execute(&*self.pool)
+        .await?;
+
+        query(
+            format!(
+                "CREATE INDEX x_header_proposer_address_blocks ON {}.blocks USING HASH (header_proposer_address);",
+                self.network
+            )
+            .as_str(),
+        )
+        .execute(&*self.pool)
+        .await?;
+
+        query(
+            format!(
+                "CREATE INDEX x_event_type_events ON {}.events USING HASH (event_type);",
+                self.network
+            )
+            .as_str(),
+        )
+        .execute(&*self.pool)
+        .await?;
+
+        query(
+            format!(
+                "CREATE INDEX x_tx_hash_events ON {}.events USING HASH (tx_hash);",
+                self.network
+            )
+            .as_str(),
+        )
+        .execute(&*self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self, block_id))]
+    pub async fn block_by_id(&self, block_id: &[u8]) -> Result<Option<Row>, Error> {
+        // query for the block if it exists
+        let str = format!(
+            "SELECT * FROM {}.{BLOCKS_TABLE_NAME} WHERE block_id=$1",
+            self.network
+        );
+        query(&str)
+            .bind(block_id)
+            .fetch_optional(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    /// Returns the block at `block_height` if present, otherwise returns an Error.
+    #[instrument(skip(self))]
+    pub async fn block_by_height(&self, block_height: u32) -> Result<Option<Row>, Error> {
+        let str = format!(
+            "SELECT * FROM {}.{BLOCKS_TABLE_NAME} WHERE header_height={block_height}",
+            self.network
+        );
+
+        query(&str)
+            .fetch_optional(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the latest block, otherwise returns an Error.
+    pub async fn get_last_block(&self) -> Result<Row, Error> {
+        let str = format!("SELECT * FROM {0}.{BLOCKS_TABLE_NAME} WHERE header_height = (SELECT MAX(header_height) FROM {0}.{BLOCKS_TABLE_NAME})", self.network);
+
+        // use query_one as the row matching max height is unique.
+        query(&str)
+            .fetch_one(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the id of the block stored at `block_height`, if any.
+    pub async fn block_id_by_height(&self, block_height: u32) -> Result<Option<Vec<u8>>, Error> {
+        let str = format!(
+            "SELECT block_id FROM {}.{BLOCKS_TABLE_NAME} WHERE header_height = $1",
+            self.network
+        );
+
+        let row = query(&str)
+            .bind(block_height as i32)
+            .fetch_optional(&*self.pool)
+            .await?;
+
+        row.map(|r| r.try_get("block_id"))
+            .transpose()
+            .map_err(Error::from)
+    }
+
+    /// Returns the heights between `from` and `to` (both included) for which
+    /// there is no block stored, in ascending order.
+    #[instrument(skip(self))]
+    pub async fn missing_heights(&self, from: u32, to: u32) -> Result<Vec<u32>, Error> {
+        let str = format!(
+            "SELECT h AS header_height
+            FROM generate_series($1::INTEGER, $2::INTEGER) AS h
+            WHERE NOT EXISTS (SELECT 1 FROM {}.{BLOCKS_TABLE_NAME} b WHERE b.header_height = h)
+            ORDER BY h",
+            self.network
+        );
+
+        let rows = query(&str)
+            .bind(from as i32)
+            .bind(to as i32)
+            .fetch_all(&*self.pool)
+            .await?;
+
+        rows.iter()
+            .map(|r| r.try_get::<i32, _>("header_height").map(|h| h as u32))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(Error::from)
+    }
+
+    /// Removes every block with a height greater or equal than `height`
+    /// along with the rows of all the tables referencing them, the heights of
+    /// the consensus keys being computed again.
+    ///
+    /// Everything is deleted within a single postgres-transaction so a failure
+    /// in the middle does not leave orphan rows behind.
+    #[instrument(skip(self))]
+    pub async fn rollback_from(&self, height: u32) -> Result<(), Error> {
+        let mut sqlx_tx = self.transaction().await?;
+
+        // rows referencing the blocks go first, transactions have a
+        // foreign key on blocks once indexes are created.
+        for table in BLOCK_DATA_TABLES {
+            let str = format!(
+                "DELETE FROM {0}.{table} WHERE block_id IN (SELECT block_id FROM {0}.{BLOCKS_TABLE_NAME} WHERE header_height >= $1)",
+                self.network
+            );
+
+            query(&str)
+                .bind(height as i32)
+                .execute(&mut sqlx_tx)
+                .await?;
+        }
+
+        let str = format!(
+            "DELETE FROM {}.{BLOCKS_TABLE_NAME} WHERE header_height >= $1",
+            self.network
+        );
+
+        let res = query(&str)
+            .bind(height as i32)
+            .execute(&mut sqlx_tx)
+            .await?;
+
+        Self::refresh_validator_tm_addresses(&mut sqlx_tx, &self.network).await?;
+
+        sqlx_tx.commit().await?;
+
+        info!(
+            "Rolled back {} blocks starting at height {}",
+            res.rows_affected(),
+            height
+        );
+
+        increment_counter!(INDEXER_ROLLBACK_COUNTER, "chain_name" => self.network.clone());
+
+        Ok(())
+    }
+
+    /// Returns the blocks between `from` and `to` (both included) in ascending
+    /// order, each one as a JSON document holding the block row along with the
+    /// rows of every table referencing it, keyed by table name.
+    ///
+    /// Keys are named after the table columns, binary values are hex
+    /// encoded with a `\x` prefix as postgres does.
+    #[instrument(skip(self))]
+    pub async fn export_blocks(&self, from: u32, to: u32) -> Result<Vec<String>, Error> {
+        let tables = BLOCK_DATA_TABLES
+            .iter()
+            .map(|table| {
+                format!(
+                    "'{table}', COALESCE((SELECT jsonb_agg(to_jsonb(r)) FROM {0}.{table} r WHERE r.block_id = b.block_id), '[]'::jsonb)",
+                    self.network
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+
+        let str = format!(
+            "SELECT json_build_object('block', to_jsonb(b), {tables})::TEXT AS block
+            FROM {0}.{BLOCKS_TABLE_NAME} b
+            WHERE b.header_height BETWEEN $1 AND $2
+            ORDER BY b.header_height",
+            self.network
+        );
+
+        let rows = query(&str)
+            .bind(from as i32)
+            .bind(to as i32)
+            .fetch_all(&*self.pool)
+            .await?;
+
+        rows.iter()
+            .map(|r| r.try_get::<String, _>("block"))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(Error::from)
+    }
+
+    /// Saves a block exported by [Database::export_blocks] along with the
+    /// rows of the tables referencing it. Tables missing from the export,
+    /// like the ones added after it was made, are left empty.
+    #[instrument(skip(self, block))]
+    pub async fn import_block(&self, block: &serde_json::Value) -> Result<(), Error> {
+        let mut sqlx_tx = self.transaction().await?;
+
+        let str = format!(
+            "INSERT INTO {0}.{BLOCKS_TABLE_NAME}
+            SELECT * FROM jsonb_populate_record(NULL::{0}.{BLOCKS_TABLE_NAME}, $1)
+            RETURNING block_id, header_height",
+            self.network
+        );
+
+        let row = query(&str)
+            .bind(&block["block"])
+            .fetch_one(&mut sqlx_tx)
+            .await?;
+        let block_id: Vec<u8> = row.try_get("block_id")?;
+        let height: i32 = row.try_get("header_height")?;
+
+        for table in BLOCK_DATA_TABLES {
+            if block[table].is_null() {
+                continue;
+            }
+
+            let str = format!(
+                "INSERT INTO {0}.{table}
+                SELECT * FROM jsonb_populate_recordset(NULL::{0}.{table}, $1)",
+                self.network
+            );
+
+            query(&str)
+                .bind(&block[table])
+                .execute(&mut sqlx_tx)
+                .await?;
+        }
+
+        let block_epoch = block[TX_TABLE_NAME]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|t| t["tx_type"] == "Wrapper")
+            .filter_map(|t| t["epoch"].as_u64())
+            .max();
+
+        Self::update_validator_tm_addresses(
+            &block_id,
+            height as u64,
+            block_epoch,
+            &mut sqlx_tx,
+            &self.network,
+        )
+        .await?;
+
+        sqlx_tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Saves the code hashes in `checksums`, the tx type and the end height
+    /// of the ones already stored with the same start height are updated.
+    #[instrument(skip(self, checksums))]
+    pub async fn save_checksums(&self, checksums: &Checksums) -> Result<(), Error> {
+        if checksums.is_empty() {
+            return Ok(());
+        }
+
+        let mut query_builder: QueryBuilder<_> = QueryBuilder::new(format!(
+            "INSERT INTO {}.{TX_CHECKSUMS_TABLE_NAME}(
+                code_hash,
+                tx_type,
+                start_height,
+                end_height
+            )",
+            self.network
+        ));
+
+        query_builder
+            .push_values(checksums.iter(), |mut b, (code_hash, range)| {
+                b.push_bind(code_hash)
+                    .push_bind(&range.tx_type)
+                    .push_bind(range.start_height as i32)
+                    .push_bind(range.end_height.map(|h| h as i32));
+            })
+            .push(
+                " ON CONFLICT (code_hash, start_height)
+                DO UPDATE SET tx_type = EXCLUDED.tx_type, end_height = EXCLUDED.end_height",
+            )
+            .build()
+            .execute(&*self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Saves the proof of stake parameters of the network, in epochs,
+    /// replacing the ones previously saved, and updates the withdrawable
+    /// epoch of the unbonds and the heights of the consensus keys accordingly.
+    #[instrument(skip(self))]
+    pub async fn save_pos_parameters(
+        &self,
+        pipeline_len: u64,
+        unbonding_len: u64,
+        cubic_slashing_window_len: u64,
+    ) -> Result<(), Error> {
+        let mut sqlx_tx = self.transaction().await?;
+
+        query(&format!(
+            "DELETE FROM {}.{POS_PARAMETERS_TABLE_NAME}",
+            self.network
+        ))
+        .execute(&mut *sqlx_tx)
+        .await?;
+
+        let str = format!(
+            "INSERT INTO {}.{POS_PARAMETERS_TABLE_NAME}(
+                pipeline_len,
+                unbonding_len,
+                cubic_slashing_window_len
+            ) VALUES ($1, $2, $3)",
+            self.network
+        );
+
+        query(&str)
+            .bind(pipeline_len as i64)
+            .bind(unbonding_len as i64)
+            .bind(cubic_slashing_window_len as i64)
+            .execute(&mut *sqlx_tx)
+            .await?;
+
+        // the unbonds saved before, or with other parameters
+        let str = format!(
+            "UPDATE {0}.{UNBONDS_TABLE_NAME} u
+            SET withdrawable_epoch = {WITHDRAWABLE_EPOCH}
+            FROM {0}.{POS_PARAMETERS_TABLE_NAME} p
+            WHERE u.withdrawable_epoch IS DISTINCT FROM {WITHDRAWABLE_EPOCH}",
+            self.network
+        );
+
+        query(&str).execute(&mut *sqlx_tx).await?;
+
+        // the pipeline epoch of the consensus keys depends on them
+        Self::refresh_validator_tm_addresses(&mut sqlx_tx, &self.network).await?;
+
+        sqlx_tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Saves the consensus keys of the validators created at genesis,
+    /// replacing the ones previously saved.
+    #[instrument(skip(self, validators))]
+    pub async fn save_genesis_validators(
+        &self,
+        validators: &[(Address, PublicKey)],
+    ) -> Result<(), Error> {
+        let mut sqlx_tx = self.transaction().await?;
+
+        query(&format!(
+            "DELETE FROM {}.{GENESIS_VALIDATORS_TABLE_NAME}",
+            self.network
+        ))
+        .execute(&mut *sqlx_tx)
+        .await?;
+
+        if !validators.is_empty() {
+            let mut query_builder: QueryBuilder<_> = QueryBuilder::new(format!(
+                "INSERT INTO {}.{GENESIS_VALIDATORS_TABLE_NAME}(
+                    address,
+                    consensus_key,
+                    tm_address
+                )",
+                self.network
+            ));
+
+            query_builder
+                .push_values(validators.iter(), |mut b, (address, consensus_key)| {
+                    b.push_bind(address.to_string())
+                        .push_bind(consensus_key.to_string())
+                        .push_bind(tm_consensus_key_raw_hash(consensus_key));
+                })
+                .build()
+                .execute(&mut *sqlx_tx)
+                .await?;
+        }
+
+        Self::refresh_validator_tm_addresses(&mut sqlx_tx, &self.network).await?;
+
+        sqlx_tx.commit().await.map_err(Error::from)
+    }
+
+    /// Returns all the code hashes stored in the `tx_checksums` table.
+    #[instrument(skip(self))]
+    pub async fn load_checksums(&self) -> Result<Checksums, Error> {
+        let str = format!(
+            "SELECT code_hash, tx_type, start_height, end_height FROM {}.{TX_CHECKSUMS_TABLE_NAME}",
+            self.network
+        );
+
+        let rows = query(&str).fetch_all(&*self.pool).await?;
+
+        let mut checksums = Checksums::default();
+        for row in rows.iter() {
+            let start_height: i32 = row.try_get("start_height")?;
+            let end_height: Option<i32> = row.try_get("end_height")?;
+
+            checksums.insert(
+                row.try_get("code_hash")?,
+                ChecksumRange {
+                    tx_type: row.try_get("tx_type")?,
+                    start_height: start_height as u64,
+                    end_height: end_height.map(|h| h as u64),
+                },
+            );
+        }
+
+        Ok(checksums)
+    }
+
+    /// Returns the events of type `event_type` having the attribute `key`,
+    /// set to `value` if given, newest first. All filters are optional.
+    #[instrument(skip(self))]
+    pub async fn get_events(
+        &self,
+        event_type: Option<&str>,
+        key: Option<&str>,
+        value: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Row>, Error> {
+        let str = format!(
+            "SELECT * FROM {}.{EVENTS_TABLE_NAME}
+            WHERE ($1::TEXT IS NULL OR event_type = $1)
+            AND ($2::TEXT IS NULL OR jsonb_exists(attributes, $2))
+            AND ($3::TEXT IS NULL OR attributes->>$2 = $3)
+            ORDER BY height DESC, event_index
+            LIMIT $4 OFFSET $5",
+            self.network
+        );
+
+        query(&str)
+            .bind(event_type)
+            .bind(key)
+            .bind(value)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    /// Decodes again the data of the transactions kept raw between the
+    /// heights `from` and `to` (both included), only those of type `tx_type`
+    /// if set, and updates their `data`. Returns the number of transactions
+    /// updated.
+    ///
+    /// As while indexing, failed transactions are skipped. The decoders
+    /// save their own data again, the transactions being decoded in the
+    /// order they were executed (by height, then by index in their block)
+    /// so the ones depending on earlier ones, like the withdrawals on the
+    /// unbonds, see them.
+    #[instrument(skip(self, checksums, decoders))]
+    pub async fn redecode_txs(
+        &self,
+        checksums: &Checksums,
+        decoders: &DecoderRegistry,
+        tx_type: Option<&str>,
+        from: u32,
+        to: u32,
+    ) -> Result<usize, Error> {
+        let str = format!(
+            "SELECT r.hash, r.block_id, r.raw, t.code, b.header_height,
+                (SELECT w.epoch FROM {0}.{TX_WRAPPERS_TABLE_NAME} x
+                JOIN {0}.{TX_TABLE_NAME} w ON w.hash = x.wrapper_hash
+                WHERE x.inner_hash = r.hash AND w.epoch IS NOT NULL LIMIT 1) AS epoch
+            FROM {0}.{TX_RAW_TABLE_NAME} r
+            JOIN {0}.{TX_TABLE_NAME} t ON t.hash = r.hash AND t.block_id = r.block_id
+            JOIN {0}.{BLOCKS_TABLE_NAME} b ON b.block_id = r.block_id
+            WHERE b.header_height BETWEEN $1 AND $2
+            AND t.return_code = 0
+            ORDER BY b.header_height, r.tx_index",
+            self.network
+        );
+
+        let rows = query(&str)
+            .bind(from as i32)
+            .bind(to as i32)
+            .fetch_all(&*self.pool)
+            .await?;
+
+        let update_str = format!(
+            "UPDATE {}.{TX_TABLE_NAME} SET data = $1 WHERE hash = $2 AND block_id = $3",
+            self.network
+        );
+        let delete_str = format!(
+            "DELETE FROM {}.{DECODE_FAILURES_TABLE_NAME} WHERE hash = $1 AND block_id = $2",
+            self.network
+        );
+
+        let mut sqlx_tx = self.transaction().await?;
+        let mut count = 0;
+
+        for row in rows {
+            let hash: Vec<u8> = row.try_get("hash")?;
+            let block_id: Vec<u8> = row.try_get("block_id")?;
+            let raw: Vec<u8> = row.try_get("raw")?;
+            let code: Option<Vec<u8>> = row.try_get("code")?;
+            let height: i32 = row.try_get("header_height")?;
+            let epoch: Option<i64> = row.try_get("epoch")?;
+
+            let code_hex = hex::encode(code.unwrap_or_default());
+            let type_tx = checksums
+                .tx_type(&code_hex, height as u64)
+                .unwrap_or("unknown");
+
+            if matches!(tx_type, Some(t) if t != type_tx) {
+                continue;
+            }
+
+            let tx =
+                Tx::try_from(raw.as_slice()).map_err(|e| Error::InvalidTxData(e.to_string()))?;
+            let data = tx.data().unwrap_or_default();
+
+            match decoders.decode(type_tx, &data) {
+                Ok(value) => {
+                    if let Some(decoder) = decoders.get(type_tx) {
+                        let tx = DecodedTx {
+                            hash: &hash,
+                            block_id: &block_id,
+                            height: height as u64,
+                            epoch: epoch.map(|e| e as u64),
+                            data: &value,
+                            raw: &data,
+                            tx: &tx,
+                        };
+                        match decoder.save(&tx, &mut sqlx_tx, &self.network).await {
+                            Ok(()) => {}
+                            Err(Error::InvalidTxData(e)) => {
+                                tracing::warn!(
+                                    "Failed to save {} transaction {}: {}",
+                                    type_tx,
+                                    hex::encode(&hash),
+                                    e
+                                );
+                                // the transaction stays a failure
+                                continue;
+                            }
+                            Err(e) => return Err(e),
+                        }
+                    }
+
+                    query(&update_str)
+                        .bind(&value)
+                        .bind(&hash)
+                        .bind(&block_id)
+                        .execute(&mut *sqlx_tx)
+                        .await?;
+                    // the transaction is not a failure anymore
+                    query(&delete_str)
+                        .bind(&hash)
+                        .bind(&block_id)
+                        .execute(&mut *sqlx_tx)
+                        .await?;
+
+                    count += 1;
+                }
+                Err(e) => tracing::warn!(
+                    "Failed to decode {} transaction {}: {}",
+                    type_tx,
+                    hex::encode(&hash),
+                    e
+                ),
+            }
+        }
+
+        // consensus keys might have been saved again
+        Self::refresh_validator_tm_addresses(&mut sqlx_tx, &self.network).await?;
+
+        sqlx_tx.commit().await?;
+
+        Ok(count)
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the ibc fungible token transfers, newest first, optionally
+    /// filtered by address (sender or receiver), channel and denom.
+    pub async fn get_ibc_transfers(
+        &self,
+        address: Option<&str>,
+        channel: Option<&str>,
+        denom: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Row>, Error> {
+        let str = format!(
+            "SELECT block_id, height, tx_hash, direction, sender, receiver, denom,
+                amount::TEXT AS amount, port, channel, sequence
+            FROM {}.{IBC_TRANSFERS_TABLE_NAME}
+            WHERE ($1::TEXT IS NULL OR sender = $1 OR receiver = $1)
+            AND ($2::TEXT IS NULL OR channel = $2)
+            AND ($3::TEXT IS NULL OR denom = $3)
+            ORDER BY height DESC
+            LIMIT $4 OFFSET $5",
+            self.network
+        );
+
+        query(&str)
+            .bind(address)
+            .bind(channel)
+            .bind(denom)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the fees paid by the wrappers included between the heights
+    /// `start` and `end` (both included), summed by fee token.
+    pub async fn get_fees(&self, start: u32, end: u32) -> Result<Vec<Row>, Error> {
+        let str = format!(
+            "SELECT t.fee_token, SUM(t.fee_paid)::TEXT AS fee_paid,
+                SUM(d.gas_used)::BIGINT AS gas_used, COUNT(*) AS tx_count
+            FROM {0}.{TX_TABLE_NAME} t
+            JOIN {0}.{BLOCKS_TABLE_NAME} b ON b.block_id = t.block_id
+            LEFT JOIN {0}.{TX_WRAPPERS_TABLE_NAME} x ON x.wrapper_hash = t.hash
+            LEFT JOIN {0}.{TX_TABLE_NAME} d ON d.hash = x.inner_hash
+            WHERE t.tx_type = 'Wrapper'
+            AND b.header_height BETWEEN $1 AND $2
+            GROUP BY t.fee_token
+            ORDER BY t.fee_token",
+            self.network
+        );
+
+        query(&str)
+            .bind(start as i32)
+            .bind(end as i32)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the balances of `address` by token, the sum of the changes
+    /// recorded for it, not complete when some amounts are unknown.
+    pub async fn get_balances(&self, address: &str) -> Result<Vec<Row>, Error> {
+        let str = format!(
+            "SELECT token, SUM(amount)::TEXT AS balance
+            FROM {}.{BALANCE_CHANGES_TABLE_NAME}
+            WHERE address = $1
+            GROUP BY token
+            ORDER BY token",
+            self.network
+        );
+
+        query(&str)
+            .bind(address)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the balance changes of `address`, newest first, optionally
+    /// filtered by token.
+    pub async fn get_balance_history(
+        &self,
+        address: &str,
+        token: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Row>, Error> {
+        let str = format!(
+            "SELECT block_id, height, tx_hash, token, amount::TEXT AS amount, kind
+            FROM {}.{BALANCE_CHANGES_TABLE_NAME}
+            WHERE address = $1
+            AND ($2::TEXT IS NULL OR token = $2)
+            ORDER BY height DESC, kind, tx_hash
+            LIMIT $3 OFFSET $4",
+            self.network
+        );
+
+        query(&str)
+            .bind(address)
+            .bind(token)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the positions of `delegator` by validator: the amount bonded,
+    /// the amount unbonded but not withdrawn yet and the amount withdrawn,
+    /// as of `height` if given.
+    pub async fn get_delegator_positions(
+        &self,
+        delegator: &str,
+        height: Option<i32>,
+    ) -> Result<Vec<Row>, Error> {
+        let str = format!(
+            "WITH bonded AS (
+                SELECT validator, SUM(amount) AS amount
+                FROM {0}.{BOND_CHANGES_TABLE_NAME}
+                WHERE delegator = $1
+                AND ($2::INTEGER IS NULL OR height <= $2)
+                GROUP BY validator
+            ), unbonded AS (
+                SELECT u.validator,
+                    SUM(u.amount) FILTER (WHERE w.tx_hash IS NULL) AS unbonding,
+                    SUM(u.amount) FILTER (WHERE w.tx_hash IS NOT NULL) AS withdrawn
+                FROM {0}.{UNBONDS_TABLE_NAME} u
+                LEFT JOIN {0}.{UNBOND_WITHDRAWALS_TABLE_NAME} w
+                    ON w.unbond_tx_hash = u.tx_hash
+                    AND w.unbond_block_id = u.block_id
+                    AND ($2::INTEGER IS NULL OR w.height <= $2)
+                WHERE (u.source = $1 OR (u.source IS NULL AND u.validator = $1))
+                AND ($2::INTEGER IS NULL OR u.height <= $2)
+                GROUP BY u.validator
+            )
+            SELECT COALESCE(b.validator, u.validator) AS validator,
+                COALESCE(b.amount, 0)::TEXT AS bonded,
+                COALESCE(u.unbonding, 0)::TEXT AS unbonding,
+                COALESCE(u.withdrawn, 0)::TEXT AS withdrawn
+            FROM bonded b
+            FULL OUTER JOIN unbonded u ON u.validator = b.validator
+            ORDER BY 1",
+            self.network
+        );
+
+        query(&str)
+            .bind(delegator)
+            .bind(height)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the unbonds of `delegator`, newest first, with the withdrawal
+    /// they have been withdrawn by if any.
+    pub async fn get_delegator_unbonds(
+        &self,
+        delegator: &str,
+        pending: bool,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Row>, Error> {
+        let str = format!(
+            "SELECT u.block_id, u.height, u.epoch, u.tx_hash, u.validator,
+                u.amount::TEXT AS amount, u.withdrawable_epoch, w.tx_hash AS withdrawal_tx_hash,
+                w.height AS withdrawal_height
+            FROM {0}.{UNBONDS_TABLE_NAME} u
+            LEFT JOIN {0}.{UNBOND_WITHDRAWALS_TABLE_NAME} w
+                ON w.unbond_tx_hash = u.tx_hash AND w.unbond_block_id = u.block_id
+            WHERE (u.source = $1 OR (u.source IS NULL AND u.validator = $1))
+            AND (NOT $2 OR w.tx_hash IS NULL)
+            ORDER BY u.height DESC, u.tx_hash
+            LIMIT $3 OFFSET $4",
+            self.network
+        );
+
+        query(&str)
+            .bind(delegator)
+            .bind(pending)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the delegators having tokens bonded to `validator`, as of
+    /// `height` if given, biggest first.
+    pub async fn get_validator_delegators(
+        &self,
+        validator: &str,
+        height: Option<i32>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Row>, Error> {
+        let str = format!(
+            "SELECT delegator, SUM(amount)::TEXT AS bonded
+            FROM {}.{BOND_CHANGES_TABLE_NAME}
+            WHERE validator = $1
+            AND ($2::INTEGER IS NULL OR height <= $2)
+            GROUP BY delegator
+            HAVING SUM(amount) > 0
+            ORDER BY SUM(amount) DESC, delegator
+            LIMIT $3 OFFSET $4",
+            self.network
+        );
+
+        query(&str)
+            .bind(validator)
+            .bind(height)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the latest height value, otherwise returns an Error.
+    pub async fn get_last_height(&self) -> Result<Row, Error> {
+        let str = format!(
+            "SELECT MAX(header_height) AS header_height FROM {}.{BLOCKS_TABLE_NAME}",
+            self.network
+        );
 
         // use query_one as the row matching max height is unique.
         query(&str)
@@ -1034,12 +3751,34 @@ impl Database {
             .map_err(Error::from)
     }
 
+    #[instrument(skip(self))]
+    /// Returns the lowest height value, otherwise returns an Error.
+    pub async fn get_first_height(&self) -> Result<Row, Error> {
+        let str = format!(
+            "SELECT MIN(header_height) AS header_height FROM {}.{BLOCKS_TABLE_NAME}",
+            self.network
+        );
+
+        query(&str)
+            .fetch_one(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
     #[instrument(skip(self))]
     /// Returns Transaction identified by hash
     pub async fn get_tx(&self, hash: &[u8]) -> Result<Option<Row>, Error> {
-        // query for transaction with hash
+        // query for transaction with hash, along with its wrapper if it is
+        // a decrypted tx or its inner tx if it is a wrapper.
         let str = format!(
-            "SELECT * FROM {}.{TX_TABLE_NAME} WHERE hash=$1",
+            "SELECT t.hash, t.block_id, t.tx_type, w.wrapper_hash AS wrapper_id, i.inner_hash,
+                t.fee_amount_per_gas_unit, t.fee_token, t.gas_limit_multiplier, t.code, t.data, t.return_code,
+                t.gas_used, t.info, t.log, COALESCE(t.fee_paid, wt.fee_paid)::TEXT AS fee_paid
+            FROM {0}.{TX_TABLE_NAME} t
+            LEFT JOIN {0}.{TX_WRAPPERS_TABLE_NAME} w ON w.inner_hash = t.hash
+            LEFT JOIN {0}.{TX_WRAPPERS_TABLE_NAME} i ON i.wrapper_hash = t.hash
+            LEFT JOIN {0}.{TX_TABLE_NAME} wt ON wt.hash = w.wrapper_hash
+            WHERE t.hash=$1",
             self.network
         );
 
@@ -1051,11 +3790,18 @@ impl Database {
     }
 
     #[instrument(skip(self))]
-    /// Returns Transaction identified by hash
+    /// Returns the transfers, bonds, unbonds, redelegations and withdrawals
+    /// involving `address`
     pub async fn get_txs_by_address(&self, address: &String) -> Result<Vec<Row>, Error> {
-        // query for transaction with hash
         let str = format!(
-            "SELECT * FROM {}.{TX_TABLE_NAME} WHERE data->>'source' = $1 OR data->>'target' = $1;",
+            "SELECT t.* FROM {0}.{TX_TABLE_NAME} t
+            JOIN (
+                SELECT tx_hash, block_id FROM {0}.{TRANSFERS_TABLE_NAME} WHERE source = $1 OR target = $1
+                UNION SELECT tx_hash, block_id FROM {0}.{BONDS_TABLE_NAME} WHERE source = $1
+                UNION SELECT tx_hash, block_id FROM {0}.{UNBONDS_TABLE_NAME} WHERE source = $1
+                UNION SELECT tx_hash, block_id FROM {0}.{REDELEGATIONS_TABLE_NAME} WHERE owner = $1
+                UNION SELECT tx_hash, block_id FROM {0}.{WITHDRAWALS_TABLE_NAME} WHERE source = $1
+            ) a ON a.tx_hash = t.hash AND a.block_id = t.block_id;",
             self.network
         );
 
@@ -1244,54 +3990,389 @@ impl Database {
             .map_err(Error::from)
     }
 
+    #[instrument(skip(self))]
+    /// Returns the governance proposals, newest first.
+    pub async fn get_proposals(&self, limit: i64, offset: i64) -> Result<Vec<Row>, Error> {
+        let str = format!(
+            "SELECT * FROM {}.{PROPOSALS_TABLE_NAME}
+            ORDER BY proposal_id DESC
+            LIMIT $1 OFFSET $2",
+            self.network
+        );
+
+        query(&str)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the governance proposal identified by `proposal_id`.
+    pub async fn get_proposal(&self, proposal_id: i64) -> Result<Option<Row>, Error> {
+        let str = format!(
+            "SELECT * FROM {}.{PROPOSALS_TABLE_NAME} WHERE proposal_id = $1",
+            self.network
+        );
+
+        query(&str)
+            .bind(proposal_id)
+            .fetch_optional(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the votes cast on the proposal `proposal_id`, oldest first.
     pub async fn vote_proposal_data(&self, proposal_id: i64) -> Result<Vec<Row>, Error> {
-        let query = format!(
-            "SELECT data FROM {}.transactions WHERE code = '\\xccdbe81f664ca6c2caa11426927093dc10ed95e75b3f2f45bffd8514fee47cd0' AND (data->>'id')::int = $1;",
+        let str = format!(
+            "SELECT * FROM {}.{VOTES_TABLE_NAME}
+            WHERE proposal_id = $1
+            ORDER BY height, tx_hash",
+            self.network
+        );
+
+        query(&str)
+            .bind(proposal_id)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the number of votes by option on the proposal `proposal_id`,
+    /// only the last vote of a voter is counted.
+    pub async fn get_proposal_tally(&self, proposal_id: i64) -> Result<Vec<Row>, Error> {
+        let str = format!(
+            "SELECT vote, COUNT(*) AS count
+            FROM (
+                SELECT DISTINCT ON (voter) voter, vote
+                FROM {}.{VOTES_TABLE_NAME}
+                WHERE proposal_id = $1
+                ORDER BY voter, height DESC
+            ) v
+            GROUP BY vote",
             self.network
         );
 
-        // Execute the query and fetch the first row (if any)
-        sqlx::query(&query)
+        query(&str)
             .bind(proposal_id)
             .fetch_all(&*self.pool)
             .await
             .map_err(Error::from)
     }
 
-    // Return the number of commits signed by the `validator_address` in a range of 500 blocks.
-    // It is use to calculate the validator uptime.
+    #[instrument(skip(self))]
+    /// Returns the current state of the validators, in the order they
+    /// registered.
+    pub async fn get_validators(&self, limit: i64, offset: i64) -> Result<Vec<Row>, Error> {
+        let str = format!(
+            "SELECT {VALIDATOR_COLUMNS}
+            FROM {}.{VALIDATORS_VIEW_NAME}
+            ORDER BY registered_height, address
+            LIMIT $1 OFFSET $2",
+            self.network
+        );
+
+        query(&str)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the current state of the validator `address`.
+    pub async fn get_validator(&self, address: &str) -> Result<Option<Row>, Error> {
+        let str = format!(
+            "SELECT {VALIDATOR_COLUMNS}
+            FROM {}.{VALIDATORS_VIEW_NAME}
+            WHERE address = $1",
+            self.network
+        );
+
+        query(&str)
+            .bind(address)
+            .fetch_optional(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the history of the validator `address` saved in `table`,
+    /// oldest first. The numeric columns are returned as text.
+    async fn get_validator_history(&self, table: &str, address: &str) -> Result<Vec<Row>, Error> {
+        let columns = match table {
+            VALIDATOR_COMMISSIONS_TABLE_NAME => {
+                "block_id, height, epoch, tx_hash, validator,
+                commission_rate::TEXT AS commission_rate,
+                max_commission_rate_change::TEXT AS max_commission_rate_change"
+            }
+            _ => "*",
+        };
+
+        let str = format!(
+            "SELECT {columns} FROM {}.{table}
+            WHERE validator = $1
+            ORDER BY height, tx_hash",
+            self.network
+        );
+
+        query(&str)
+            .bind(address)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    /// Returns the changes of state of the validator `address`.
+    pub async fn get_validator_states(&self, address: &str) -> Result<Vec<Row>, Error> {
+        self.get_validator_history(VALIDATOR_STATES_TABLE_NAME, address)
+            .await
+    }
+
+    /// Returns the commission rates of the validator `address`.
+    pub async fn get_validator_commissions(&self, address: &str) -> Result<Vec<Row>, Error> {
+        self.get_validator_history(VALIDATOR_COMMISSIONS_TABLE_NAME, address)
+            .await
+    }
+
+    /// Returns the metadata changes of the validator `address`.
+    pub async fn get_validator_metadata(&self, address: &str) -> Result<Vec<Row>, Error> {
+        self.get_validator_history(VALIDATOR_METADATA_TABLE_NAME, address)
+            .await
+    }
+
+    /// Returns the consensus keys of the validator `address`.
+    pub async fn get_validator_consensus_keys(&self, address: &str) -> Result<Vec<Row>, Error> {
+        self.get_validator_history(VALIDATOR_CONSENSUS_KEYS_TABLE_NAME, address)
+            .await
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the Tendermint addresses of the validator `address` with the
+    /// range of heights they have been set over, oldest first.
+    pub async fn get_validator_tm_addresses(&self, address: &str) -> Result<Vec<Row>, Error> {
+        let str = format!(
+            "SELECT tm_address, start_height, end_height
+            FROM {}.{VALIDATOR_TM_ADDRESSES_TABLE_NAME}
+            WHERE validator = $1 AND start_height IS NOT NULL
+            ORDER BY start_height",
+            self.network
+        );
+
+        query(&str)
+            .bind(address)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the Namada address of the validator whose consensus key has
+    /// the Tendermint address `tm_address` (upper case hex), if known.
+    pub async fn validator_by_tm_address(&self, tm_address: &str) -> Result<Option<String>, Error> {
+        let str = format!(
+            "SELECT validator FROM {}.{VALIDATOR_TM_ADDRESSES_TABLE_NAME}
+            WHERE tm_address = $1 AND start_height IS NOT NULL
+            LIMIT 1",
+            self.network
+        );
+
+        let row = query(&str)
+            .bind(tm_address)
+            .fetch_optional(&*self.pool)
+            .await?;
+
+        row.map(|r| r.try_get("validator"))
+            .transpose()
+            .map_err(Error::from)
+    }
+
+    // Return the number of commits signed by any of the `validator_addresses` in the blocks
+    // of the heights `start` to `end`, nil votes left out. It is use to calculate the
+    // validator uptime.
     pub async fn validator_uptime(
         &self,
-        validator_address: &[u8],
-        start: Option<&i32>,
-        end: Option<&i32>,
+        validator_addresses: &[Vec<u8>],
+        start: i32,
+        end: i32,
     ) -> Result<Row, Error> {
-        // if no parameters defined we return result on the last 500 blocks
-        let mut q = format!(
+        let q = format!(
             "SELECT COUNT(*)
                 FROM {0}.commit_signatures
-                WHERE validator_address = $1
+                WHERE validator_address = ANY($1)
+                AND block_id_flag = {BLOCK_ID_FLAG_COMMIT}
                 AND block_id IN
-                    (SELECT block_id FROM {0}.blocks WHERE header_height BETWEEN (SELECT MAX(header_height) FROM {0}.blocks) - 499 AND (SELECT MAX(header_height) FROM {0}.blocks))",
+                    (SELECT block_id FROM {0}.blocks WHERE header_height BETWEEN $2 AND $3)",
             self.network,
         );
 
-        if start.is_some() && end.is_some() {
-            q = format!(
-                "SELECT COUNT(*)
-                    FROM {0}.commit_signatures
-                    WHERE validator_address = $1
-                    AND block_id IN
-                        (SELECT block_id FROM {0}.blocks WHERE header_height BETWEEN ($2 + 1) AND $3)",
-                self.network,
-            );
-        }
-
         query(&q)
-            .bind(validator_address)
+            .bind(validator_addresses)
+            .bind(start)
+            .bind(end)
+            .fetch_one(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the number of commit signatures of each validator in the blocks
+    /// of the heights `start` to `end`, the validators whose consensus key is
+    /// used by `end` included even without any signature, along with their
+    /// Tendermint address (upper case hex) as of `end`. The signatures of the
+    /// Tendermint addresses of unknown validators are returned by address.
+    pub async fn validators_uptime(&self, start: i32, end: i32) -> Result<Vec<Row>, Error> {
+        let str = format!(
+            "WITH s AS (
+                SELECT UPPER(encode(c.validator_address, 'hex')) AS tm_address,
+                    COUNT(*) AS signed
+                FROM {0}.{COMMIT_SIGNATURES_TABLE_NAME} c
+                JOIN {0}.{BLOCKS_TABLE_NAME} b ON b.block_id = c.block_id
+                WHERE b.header_height BETWEEN $1 AND $2
+                AND c.block_id_flag = {BLOCK_ID_FLAG_COMMIT}
+                GROUP BY c.validator_address
+            )
+            SELECT a.validator,
+                (ARRAY_AGG(a.tm_address ORDER BY a.start_height DESC))[1] AS tm_address,
+                COALESCE(SUM(s.signed), 0)::BIGINT AS signed
+            FROM {0}.{VALIDATOR_TM_ADDRESSES_TABLE_NAME} a
+            LEFT JOIN s ON s.tm_address = a.tm_address
+            WHERE a.start_height <= $2
+            GROUP BY a.validator
+            UNION ALL
+            SELECT NULL, s.tm_address, s.signed
+            FROM s
+            WHERE NOT EXISTS (
+                SELECT 1 FROM {0}.{VALIDATOR_TM_ADDRESSES_TABLE_NAME} a
+                WHERE a.tm_address = s.tm_address AND a.start_height IS NOT NULL
+            )
+            ORDER BY signed DESC, tm_address",
+            self.network
+        );
+
+        query(&str)
+            .bind(start)
+            .bind(end)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the number of blocks stored between the heights `start` and `end`.
+    pub async fn count_blocks(&self, start: i32, end: i32) -> Result<i64, Error> {
+        let str = format!(
+            "SELECT COUNT(*) AS count FROM {}.{BLOCKS_TABLE_NAME} WHERE header_height BETWEEN $1 AND $2",
+            self.network
+        );
+
+        let row = query(&str)
             .bind(start)
             .bind(end)
             .fetch_one(&*self.pool)
+            .await?;
+
+        row.try_get("count").map_err(Error::from)
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the blocks proposed by any of the `tm_addresses` (upper case
+    /// hex), newest first.
+    pub async fn get_proposed_blocks(
+        &self,
+        tm_addresses: &[String],
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Row>, Error> {
+        let str = format!(
+            "SELECT block_id, header_height, header_time, header_proposer_address
+            FROM {}.{BLOCKS_TABLE_NAME}
+            WHERE header_proposer_address = ANY($1)
+            ORDER BY header_height DESC
+            LIMIT $2 OFFSET $3",
+            self.network
+        );
+
+        query(&str)
+            .bind(tm_addresses)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the number of blocks proposed by each validator between the
+    /// heights `start` and `end`, with the latest Tendermint address it
+    /// proposed with and the amount bonded to it as of `end`. The blocks of
+    /// the Tendermint addresses of unknown validators are returned by address.
+    ///
+    /// The amount bonded is the sum of the `bond_changes` table, the bonds
+    /// made at genesis are not included as no transaction made them.
+    pub async fn get_proposers(&self, start: i32, end: i32) -> Result<Vec<Row>, Error> {
+        let str = format!(
+            "SELECT (ARRAY_AGG(p.tm_address ORDER BY a.start_height DESC))[1] AS tm_address,
+                a.validator,
+                SUM(p.proposed)::BIGINT AS proposed,
+                (SELECT SUM(c.amount) FROM {0}.{BOND_CHANGES_TABLE_NAME} c
+                    WHERE c.validator = a.validator AND c.height <= $2)::TEXT AS bonded
+            FROM (
+                SELECT header_proposer_address AS tm_address, COUNT(*) AS proposed
+                FROM {0}.{BLOCKS_TABLE_NAME}
+                WHERE header_height BETWEEN $1 AND $2
+                GROUP BY header_proposer_address
+            ) p
+            LEFT JOIN {0}.{VALIDATOR_TM_ADDRESSES_TABLE_NAME} a
+                ON a.tm_address = p.tm_address AND a.start_height IS NOT NULL
+            GROUP BY a.validator, CASE WHEN a.validator IS NULL THEN p.tm_address END
+            ORDER BY proposed DESC, tm_address",
+            self.network
+        );
+
+        query(&str)
+            .bind(start)
+            .bind(end)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    /// Returns the `block_id_flag` of the signatures of any of the
+    /// `validator_addresses` in the commits of the heights `start` to `end`,
+    /// by height, a signature for the block being preferred. The commit of a
+    /// block is saved with the next block, heights whose commit is not saved
+    /// are missing and the flag is null when the validator did not sign it.
+    #[instrument(skip(self))]
+    pub async fn get_validator_signatures(
+        &self,
+        validator_addresses: &[Vec<u8>],
+        start: i32,
+        end: i32,
+    ) -> Result<Vec<Row>, Error> {
+        let str = format!(
+            "SELECT b.commit_height AS height,
+                CASE WHEN BOOL_OR(s.block_id_flag = {BLOCK_ID_FLAG_COMMIT})
+                THEN {BLOCK_ID_FLAG_COMMIT}
+                ELSE MAX(s.block_id_flag) END AS block_id_flag
+            FROM {0}.{BLOCKS_TABLE_NAME} b
+            LEFT JOIN {0}.{COMMIT_SIGNATURES_TABLE_NAME} s
+                ON s.block_id = b.block_id AND s.validator_address = ANY($1)
+            WHERE b.commit_height BETWEEN $2 AND $3
+            GROUP BY b.commit_height
+            ORDER BY b.commit_height",
+            self.network
+        );
+
+        query(&str)
+            .bind(validator_addresses)
+            .bind(start)
+            .bind(end)
+            .fetch_all(&*self.pool)
             .await
             .map_err(Error::from)
     }
@@ -1316,3 +4397,43 @@ impl Database {
         self.pool.as_ref()
     }
 }
+
+/// Result of the execution of a transaction, as found in the
+/// end block event holding its hash.
+#[derive(Debug, Default)]
+struct TxResult {
+    code: Option<i32>,
+    gas_used: Option<i64>,
+    info: Option<String>,
+    log: Option<String>,
+}
+
+impl TxResult {
+    fn find(block_results: &block_results::Response, hash: &[u8]) -> Self {
+        let hash = hex::encode(hash);
+        let mut result = Self::default();
+
+        let Some(end_events) = &block_results.end_block_events else {
+            return result;
+        };
+
+        let event = end_events.iter().find(|event| {
+            event
+                .attributes
+                .iter()
+                .any(|attr| attr.key == "hash" && attr.value.to_ascii_lowercase() == hash)
+        });
+
+        for attr in event.iter().flat_map(|e| e.attributes.iter()) {
+            match attr.key.as_str() {
+                "code" => result.code = attr.value.parse().ok(),
+                "gas_used" => result.gas_used = attr.value.parse().ok(),
+                "info" => result.info = Some(attr.value.clone()),
+                "log" => result.log = Some(attr.value.clone()),
+                _ => {}
+            }
+        }
+
+        result
+    }
+}
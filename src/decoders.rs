@@ -0,0 +1,208 @@
+use async_trait::async_trait;
+use namada_sdk::types::key::common::PublicKey;
+use namada_sdk::{
+    account::{InitAccount, UpdateAccount},
+    borsh::BorshDeserialize,
+    tx::{data::pgf::UpdateStewardCommission, Tx},
+    types::{address::Address, eth_bridge_pool::PendingTransfer},
+};
+use serde::Serialize;
+use serde_json::json;
+use sqlx::Transaction;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use crate::error::Error;
+
+mod governance;
+pub(crate) mod ibc;
+mod pos;
+mod transfer;
+
+use governance::{ProposalDecoder, VoteDecoder};
+use ibc::IbcDecoder;
+use pos::{
+    BecomeValidatorDecoder, BondDecoder, ClaimRewardsDecoder, CommissionChangeDecoder,
+    ConsensusKeyChangeDecoder, MetaDataChangeDecoder, RedelegationDecoder, ValidatorStateDecoder,
+    WithdrawDecoder,
+};
+use transfer::TransferDecoder;
+
+/// A transaction whose data has been decoded.
+#[derive(Debug)]
+pub struct DecodedTx<'a> {
+    pub hash: &'a [u8],
+    pub block_id: &'a [u8],
+    pub height: u64,
+    /// The epoch of the wrapper of the transaction, if it has been indexed.
+    pub epoch: Option<u64>,
+    /// The value returned by [TxDecoder::decode], as stored in the
+    /// `data` column of the `transactions` table.
+    pub data: &'a serde_json::Value,
+    /// The data section of the transaction, as given to [TxDecoder::decode].
+    pub raw: &'a [u8],
+    /// The decrypted transaction, to read its other sections.
+    pub tx: &'a Tx,
+}
+
+/// Decodes the data of the transactions of one type.
+///
+/// Decoders are looked up by the name the checksums give to the code of
+/// the transaction, a decoder registered for a name not found in the
+/// checksums is never used.
+#[async_trait]
+pub trait TxDecoder: Send + Sync {
+    /// The transaction type handled, e.g `tx_transfer`.
+    fn name(&self) -> &str;
+
+    /// Decodes the data section of a transaction.
+    fn decode(&self, data: &[u8]) -> Result<serde_json::Value, Error>;
+
+    /// Returns the queries creating the tables [TxDecoder::save] writes to.
+    /// They should reference the blocks by `block_id` and are expected to
+    /// use `IF NOT EXISTS` as they run every time the indexer starts.
+    fn create_tables(&self, _network: &str) -> Vec<String> {
+        vec![]
+    }
+
+    /// Saves extra data about a decoded transaction, in the same database
+    /// transaction as its block. Only called for successful transactions.
+    ///
+    /// It is called again for the same transaction when its data is decoded
+    /// again, the data previously saved must then be replaced.
+    ///
+    /// Returning [Error::InvalidTxData] records the transaction as a decode
+    /// failure instead of failing its block, nothing must have been saved
+    /// by then.
+    async fn save(
+        &self,
+        _tx: &DecodedTx<'_>,
+        _sqlx_tx: &mut Transaction<'_, sqlx::Postgres>,
+        _network: &str,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// Decoder of a transaction type whose data is a borsh encoded `T`.
+pub struct BorshDecoder<T> {
+    name: &'static str,
+    _data: PhantomData<fn() -> T>,
+}
+
+impl<T> BorshDecoder<T> {
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            _data: PhantomData,
+        }
+    }
+}
+
+impl<T> TxDecoder for BorshDecoder<T>
+where
+    T: BorshDeserialize + Serialize,
+{
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<serde_json::Value, Error> {
+        borsh_to_json::<T>(data)
+    }
+}
+
+fn borsh_to_json<T>(data: &[u8]) -> Result<serde_json::Value, Error>
+where
+    T: BorshDeserialize + Serialize,
+{
+    let value = T::try_from_slice(data)?;
+    Ok(serde_json::to_value(value)?)
+}
+
+/// The decoders used while indexing, by transaction type.
+///
+/// The default registry holds the decoders of the transaction types
+/// shipped with Namada, they can be replaced by registering another
+/// decoder with the same name.
+#[derive(Clone)]
+pub struct DecoderRegistry {
+    decoders: HashMap<String, Arc<dyn TxDecoder>>,
+}
+
+impl DecoderRegistry {
+    /// Returns a registry without any decoder.
+    pub fn empty() -> Self {
+        Self {
+            decoders: HashMap::new(),
+        }
+    }
+
+    /// Registers `decoder` for the transaction type it names, replacing
+    /// the decoder previously registered for it if any.
+    pub fn register<D: TxDecoder + 'static>(&mut self, decoder: D) -> &mut Self {
+        self.decoders
+            .insert(decoder.name().to_string(), Arc::new(decoder));
+        self
+    }
+
+    pub fn get(&self, tx_type: &str) -> Option<&dyn TxDecoder> {
+        self.decoders.get(tx_type).map(|d| d.as_ref())
+    }
+
+    /// Decodes the data of a transaction of type `tx_type`, transactions
+    /// without a decoder have a null value.
+    pub fn decode(&self, tx_type: &str, data: &[u8]) -> Result<serde_json::Value, Error> {
+        match self.get(tx_type) {
+            Some(decoder) => decoder.decode(data),
+            None => Ok(json!(null)),
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn TxDecoder> {
+        self.decoders.values().map(|d| d.as_ref())
+    }
+}
+
+impl Default for DecoderRegistry {
+    fn default() -> Self {
+        let mut registry = Self::empty();
+
+        registry
+            .register(TransferDecoder)
+            .register(BondDecoder::bond())
+            .register(BondDecoder::unbond())
+            // this is an ethereum transaction, only TransferToEthereum type
+            // is supported at the moment by namada and us.
+            .register(BorshDecoder::<PendingTransfer>::new("tx_bridge_pool"))
+            .register(VoteDecoder)
+            // nothing to do here, only check that data is a valid publicKey
+            // otherwise this transaction must not make it into the database.
+            .register(BorshDecoder::<PublicKey>::new("tx_reveal_pk"))
+            // Not much to do, just, check that the address this transactions
+            // holds in the data field is correct, or at least parsed succesfully.
+            .register(BorshDecoder::<Address>::new("tx_resign_steward"))
+            .register(BorshDecoder::<UpdateStewardCommission>::new(
+                "tx_update_steward_commission",
+            ))
+            // later accounts could be updated using tx_update_account,
+            // however there is not way so far to link those transactions to this.
+            .register(BorshDecoder::<InitAccount>::new("tx_init_account"))
+            .register(BorshDecoder::<UpdateAccount>::new("tx_update_account"))
+            .register(IbcDecoder)
+            .register(BecomeValidatorDecoder)
+            .register(ConsensusKeyChangeDecoder)
+            .register(CommissionChangeDecoder)
+            .register(MetaDataChangeDecoder)
+            .register(ClaimRewardsDecoder)
+            .register(ValidatorStateDecoder::new("tx_deactivate_validator"))
+            .register(ProposalDecoder)
+            .register(ValidatorStateDecoder::new("tx_reactivate_validator"))
+            .register(ValidatorStateDecoder::new("tx_unjail_validator"))
+            .register(RedelegationDecoder)
+            .register(WithdrawDecoder);
+
+        registry
+    }
+}
@@ -0,0 +1,159 @@
+use async_trait::async_trait;
+use namada_sdk::{
+    borsh::BorshDeserialize,
+    governance::{InitProposalData, VoteProposalData},
+};
+use sqlx::Transaction;
+use std::collections::BTreeMap;
+
+use super::{borsh_to_json, DecodedTx, TxDecoder};
+use crate::database::Database;
+use crate::error::Error;
+
+// Storage segment under which the governance keeps the proposals,
+// e.g `#<governance address>/proposal/5/content`.
+const PROPOSAL_KEY_SEGMENT: &str = "proposal";
+
+/// Returns the segments of a storage key, written as a string or
+/// serialized with its segments.
+fn key_segments(key: &serde_json::Value) -> Vec<String> {
+    match key {
+        serde_json::Value::String(key) => key.split('/').map(String::from).collect(),
+        serde_json::Value::Object(key) => key
+            .get("segments")
+            .and_then(|s| s.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|seg| seg.as_object()?.values().next()?.as_str())
+            .map(String::from)
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// Returns the id of the proposal whose storage keys have been changed by
+/// a transaction, `result` being the JSON result of its execution.
+fn proposal_id_from_result(result: &str) -> Option<u64> {
+    let result: serde_json::Value = serde_json::from_str(result).ok()?;
+
+    result
+        .get("changed_keys")?
+        .as_array()?
+        .iter()
+        .map(key_segments)
+        .find_map(|segments| {
+            segments
+                .windows(2)
+                .find(|w| w[0] == PROPOSAL_KEY_SEGMENT)
+                .and_then(|w| w[1].parse().ok())
+        })
+}
+
+/// Decodes the tx_init_proposal transactions and saves them to the
+/// `proposals` table.
+///
+/// The id of a proposal is given by the chain, it is taken from the data
+/// of the transaction if set or from the storage keys written by the
+/// transaction, as listed in the result of its execution.
+pub(super) struct ProposalDecoder;
+
+#[async_trait]
+impl TxDecoder for ProposalDecoder {
+    fn name(&self) -> &str {
+        "tx_init_proposal"
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<serde_json::Value, Error> {
+        borsh_to_json::<InitProposalData>(data)
+    }
+
+    async fn save(
+        &self,
+        tx: &DecodedTx<'_>,
+        sqlx_tx: &mut Transaction<'_, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        let proposal = InitProposalData::try_from_slice(tx.raw)?;
+
+        let id = match tx.data.get("id").and_then(|id| id.as_u64()) {
+            Some(id) => Some(id),
+            None => Database::tx_results(tx, sqlx_tx, network)
+                .await?
+                .iter()
+                .find_map(|result| proposal_id_from_result(result)),
+        };
+        let id = id.ok_or_else(|| Error::InvalidTxData("no proposal id".into()))?;
+
+        // the content is a borsh encoded map saved in an extra section
+        let content = tx
+            .tx
+            .get_section(&proposal.content)
+            .and_then(|s| s.extra_data_sec())
+            .and_then(|c| c.code.id())
+            .and_then(|c| BTreeMap::<String, String>::try_from_slice(&c).ok());
+
+        Database::save_proposal(tx, id, &proposal, content, sqlx_tx, network).await
+    }
+}
+
+/// Decodes the tx_vote_proposal transactions and saves them to the
+/// `votes` table.
+pub(super) struct VoteDecoder;
+
+#[async_trait]
+impl TxDecoder for VoteDecoder {
+    fn name(&self) -> &str {
+        "tx_vote_proposal"
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<serde_json::Value, Error> {
+        borsh_to_json::<VoteProposalData>(data)
+    }
+
+    async fn save(
+        &self,
+        tx: &DecodedTx<'_>,
+        sqlx_tx: &mut Transaction<'_, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        let vote = VoteProposalData::try_from_slice(tx.raw)?;
+
+        Database::save_vote(tx, &vote, sqlx_tx, network).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn proposal_id_from_changed_keys() {
+        let result = r##"{
+            "gas_used": 1000,
+            "changed_keys": [
+                "#tnam1q5qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqfgdh4w/counter",
+                "#tnam1q5qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqfgdh4w/proposal/5/content"
+            ]
+        }"##;
+        assert_eq!(proposal_id_from_result(result), Some(5));
+
+        let result = r##"{
+            "changed_keys": [{"segments": [
+                {"AddressSeg": "tnam1q5qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqfgdh4w"},
+                {"StringSeg": "proposal"},
+                {"StringSeg": "12"},
+                {"StringSeg": "author"}
+            ]}]
+        }"##;
+        assert_eq!(proposal_id_from_result(result), Some(12));
+    }
+
+    #[test]
+    fn proposal_id_missing() {
+        let result =
+            r##"{"changed_keys": ["#tnam1q5qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqfgdh4w/counter"]}"##;
+
+        assert_eq!(proposal_id_from_result(result), None);
+        assert_eq!(proposal_id_from_result("Check inner_tx for result."), None);
+    }
+}
@@ -0,0 +1,315 @@
+use async_trait::async_trait;
+use ibc_proto::google::protobuf::Any;
+use ibc_proto::ibc::applications::transfer::v1::MsgTransfer;
+use ibc_proto::ibc::core::channel::v1::{
+    MsgAcknowledgement, MsgChannelCloseConfirm, MsgChannelCloseInit, MsgChannelOpenAck,
+    MsgChannelOpenConfirm, MsgChannelOpenInit, MsgChannelOpenTry, MsgRecvPacket, MsgTimeout,
+    MsgTimeoutOnClose,
+};
+use ibc_proto::ibc::core::client::v1::{
+    MsgCreateClient, MsgSubmitMisbehaviour, MsgUpdateClient, MsgUpgradeClient,
+};
+use ibc_proto::ibc::core::connection::v1::{
+    MsgConnectionOpenAck, MsgConnectionOpenConfirm, MsgConnectionOpenInit, MsgConnectionOpenTry,
+};
+use prost::Message;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::Transaction;
+
+use super::{DecodedTx, TxDecoder};
+use crate::database::Database;
+use crate::error::Error;
+
+// Port bound to the fungible token transfer application.
+const TRANSFER_PORT: &str = "transfer";
+
+const MSG_TRANSFER_TYPE_URL: &str = "/ibc.applications.transfer.v1.MsgTransfer";
+const MSG_RECV_PACKET_TYPE_URL: &str = "/ibc.core.channel.v1.MsgRecvPacket";
+
+/// A fungible token transfer, sent or received through IBC.
+#[derive(Debug)]
+pub struct IbcTransfer {
+    /// `send` for a MsgTransfer, `receive` for a MsgRecvPacket.
+    pub direction: &'static str,
+    pub sender: String,
+    pub receiver: String,
+    pub denom: String,
+    /// The token moved on the Namada side, its address if it is native to
+    /// Namada and its denomination trace otherwise, e.g `transfer/channel-0/uosmo`.
+    pub token: String,
+    pub amount: String,
+    pub port: String,
+    pub channel: String,
+    /// Only known for received packets, the sequence of a sent
+    /// packet is given by the chain once executed.
+    pub sequence: Option<u64>,
+}
+
+// Data of a fungible token transfer packet (ICS-20), JSON encoded.
+#[derive(Deserialize)]
+struct FungibleTokenPacketData {
+    denom: String,
+    amount: String,
+    sender: String,
+    receiver: String,
+}
+
+fn decode_any(data: &[u8]) -> Result<Any, Error> {
+    Any::decode(data).map_err(|e| Error::InvalidTxData(e.to_string()))
+}
+
+fn decode_msg<M>(value: &[u8]) -> Result<serde_json::Value, Error>
+where
+    M: Message + Default + Serialize,
+{
+    let msg = M::decode(value).map_err(|e| Error::InvalidTxData(e.to_string()))?;
+    Ok(serde_json::to_value(msg)?)
+}
+
+/// Returns the local denomination of a token received through the channel
+/// `source_port/source_channel` on the other chain (ICS-20): a token
+/// coming back loses the prefix it was given when sent out, any other
+/// token gets the prefix of the destination channel.
+fn received_token(
+    source_port: &str,
+    source_channel: &str,
+    dest_port: &str,
+    dest_channel: &str,
+    denom: &str,
+) -> String {
+    match denom.strip_prefix(&format!("{source_port}/{source_channel}/")) {
+        Some(denom) => denom.to_string(),
+        None => format!("{dest_port}/{dest_channel}/{denom}"),
+    }
+}
+
+/// Returns the fungible token transfer of an ibc transaction, if any.
+fn ibc_transfer(data: &[u8]) -> Result<Option<IbcTransfer>, Error> {
+    let any = decode_any(data)?;
+
+    let transfer = match any.type_url.as_str() {
+        MSG_TRANSFER_TYPE_URL => {
+            let msg = MsgTransfer::decode(any.value.as_slice())
+                .map_err(|e| Error::InvalidTxData(e.to_string()))?;
+            let Some(token) = msg.token else {
+                return Ok(None);
+            };
+
+            IbcTransfer {
+                direction: "send",
+                sender: msg.sender,
+                receiver: msg.receiver,
+                // tokens are sent with their local denomination
+                token: token.denom.clone(),
+                denom: token.denom,
+                amount: token.amount,
+                port: msg.source_port,
+                channel: msg.source_channel,
+                sequence: None,
+            }
+        }
+        MSG_RECV_PACKET_TYPE_URL => {
+            let msg = MsgRecvPacket::decode(any.value.as_slice())
+                .map_err(|e| Error::InvalidTxData(e.to_string()))?;
+            let Some(packet) = msg.packet else {
+                return Ok(None);
+            };
+            if packet.destination_port != TRANSFER_PORT {
+                return Ok(None);
+            }
+            let data: FungibleTokenPacketData = match serde_json::from_slice(&packet.data) {
+                Ok(data) => data,
+                Err(e) => {
+                    tracing::warn!("Invalid ibc transfer packet data: {}", e);
+                    return Ok(None);
+                }
+            };
+
+            IbcTransfer {
+                direction: "receive",
+                sender: data.sender,
+                receiver: data.receiver,
+                token: received_token(
+                    &packet.source_port,
+                    &packet.source_channel,
+                    &packet.destination_port,
+                    &packet.destination_channel,
+                    &data.denom,
+                ),
+                denom: data.denom,
+                amount: data.amount,
+                port: packet.destination_port,
+                channel: packet.destination_channel,
+                sequence: Some(packet.sequence),
+            }
+        }
+        _ => return Ok(None),
+    };
+
+    // amounts are stored as numbers, a malformed one must not
+    // prevent the block from being saved.
+    if transfer.amount.is_empty() || !transfer.amount.bytes().all(|b| b.is_ascii_digit()) {
+        tracing::warn!("Invalid ibc transfer amount: {}", transfer.amount);
+        return Ok(None);
+    }
+
+    Ok(Some(transfer))
+}
+
+/// Decodes the IBC messages, the data of an ibc transaction being
+/// a protobuf `Any`. The fungible token transfers are also saved to
+/// the `ibc_transfers` table.
+pub(super) struct IbcDecoder;
+
+#[async_trait]
+impl TxDecoder for IbcDecoder {
+    fn name(&self) -> &str {
+        "tx_ibc"
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<serde_json::Value, Error> {
+        let any = decode_any(data)?;
+        let value = any.value.as_slice();
+
+        let body = match any.type_url.as_str() {
+            MSG_TRANSFER_TYPE_URL => decode_msg::<MsgTransfer>(value)?,
+            MSG_RECV_PACKET_TYPE_URL => decode_msg::<MsgRecvPacket>(value)?,
+            "/ibc.core.channel.v1.MsgAcknowledgement" => decode_msg::<MsgAcknowledgement>(value)?,
+            "/ibc.core.channel.v1.MsgTimeout" => decode_msg::<MsgTimeout>(value)?,
+            "/ibc.core.channel.v1.MsgTimeoutOnClose" => decode_msg::<MsgTimeoutOnClose>(value)?,
+            "/ibc.core.channel.v1.MsgChannelOpenInit" => decode_msg::<MsgChannelOpenInit>(value)?,
+            "/ibc.core.channel.v1.MsgChannelOpenTry" => decode_msg::<MsgChannelOpenTry>(value)?,
+            "/ibc.core.channel.v1.MsgChannelOpenAck" => decode_msg::<MsgChannelOpenAck>(value)?,
+            "/ibc.core.channel.v1.MsgChannelOpenConfirm" => {
+                decode_msg::<MsgChannelOpenConfirm>(value)?
+            }
+            "/ibc.core.channel.v1.MsgChannelCloseInit" => decode_msg::<MsgChannelCloseInit>(value)?,
+            "/ibc.core.channel.v1.MsgChannelCloseConfirm" => {
+                decode_msg::<MsgChannelCloseConfirm>(value)?
+            }
+            "/ibc.core.client.v1.MsgCreateClient" => decode_msg::<MsgCreateClient>(value)?,
+            "/ibc.core.client.v1.MsgUpdateClient" => decode_msg::<MsgUpdateClient>(value)?,
+            "/ibc.core.client.v1.MsgUpgradeClient" => decode_msg::<MsgUpgradeClient>(value)?,
+            "/ibc.core.client.v1.MsgSubmitMisbehaviour" => {
+                decode_msg::<MsgSubmitMisbehaviour>(value)?
+            }
+            "/ibc.core.connection.v1.MsgConnectionOpenInit" => {
+                decode_msg::<MsgConnectionOpenInit>(value)?
+            }
+            "/ibc.core.connection.v1.MsgConnectionOpenTry" => {
+                decode_msg::<MsgConnectionOpenTry>(value)?
+            }
+            "/ibc.core.connection.v1.MsgConnectionOpenAck" => {
+                decode_msg::<MsgConnectionOpenAck>(value)?
+            }
+            "/ibc.core.connection.v1.MsgConnectionOpenConfirm" => {
+                decode_msg::<MsgConnectionOpenConfirm>(value)?
+            }
+            // messages we don't know about, like the namada shielded
+            // transfers, are kept hex encoded.
+            _ => json!(hex::encode(value)),
+        };
+
+        Ok(json!({
+            "type_url": any.type_url,
+            "body": body,
+        }))
+    }
+
+    async fn save(
+        &self,
+        tx: &DecodedTx<'_>,
+        sqlx_tx: &mut Transaction<'_, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        let Some(transfer) = ibc_transfer(tx.raw)? else {
+            return Ok(());
+        };
+
+        Database::save_ibc_transfer(tx, transfer, sqlx_tx, network).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ibc_proto::cosmos::base::v1beta1::Coin;
+
+    fn msg_transfer(amount: &str) -> Vec<u8> {
+        let msg = MsgTransfer {
+            source_port: TRANSFER_PORT.to_string(),
+            source_channel: "channel-0".to_string(),
+            token: Some(Coin {
+                denom: "uosmo".to_string(),
+                amount: amount.to_string(),
+            }),
+            sender: "tnam1qqgll8x8rz9fvtdv8q6n985za0vjvgyu0udxh6fr".to_string(),
+            receiver: "osmo1x7d3gdf8q5s6r0q9q7xq8f3p6a2z6g5l6ztmvl".to_string(),
+            ..Default::default()
+        };
+
+        Any {
+            type_url: MSG_TRANSFER_TYPE_URL.to_string(),
+            value: msg.encode_to_vec(),
+        }
+        .encode_to_vec()
+    }
+
+    #[test]
+    fn decode_msg_transfer() {
+        let data = msg_transfer("1000");
+
+        let value = IbcDecoder.decode(&data).unwrap();
+        assert_eq!(value["type_url"], MSG_TRANSFER_TYPE_URL);
+        assert_eq!(value["body"]["source_channel"], "channel-0");
+
+        let transfer = ibc_transfer(&data).unwrap().unwrap();
+        assert_eq!(transfer.direction, "send");
+        assert_eq!(transfer.denom, "uosmo");
+        assert_eq!(transfer.token, "uosmo");
+        assert_eq!(transfer.amount, "1000");
+        assert_eq!(transfer.port, TRANSFER_PORT);
+        assert_eq!(transfer.channel, "channel-0");
+        assert_eq!(transfer.sequence, None);
+    }
+
+    #[test]
+    fn decode_unknown_message() {
+        let data = Any {
+            type_url: "/ibc.applications.unknown.v1.MsgUnknown".to_string(),
+            value: vec![0xde, 0xad, 0xbe, 0xef],
+        }
+        .encode_to_vec();
+
+        let value = IbcDecoder.decode(&data).unwrap();
+        assert_eq!(value["body"], "deadbeef");
+        assert!(ibc_transfer(&data).unwrap().is_none());
+    }
+
+    #[test]
+    fn received_token_trace() {
+        // a token of the other chain gets the prefix of our channel
+        assert_eq!(
+            received_token("transfer", "channel-4", "transfer", "channel-0", "uosmo"),
+            "transfer/channel-0/uosmo"
+        );
+        // a token sent out by Namada is back to its address
+        assert_eq!(
+            received_token(
+                "transfer",
+                "channel-4",
+                "transfer",
+                "channel-0",
+                "transfer/channel-4/tnam1qxgfw7myv4dh0qna4hq0xdg6lx77fzl7dcem8h7e"
+            ),
+            "tnam1qxgfw7myv4dh0qna4hq0xdg6lx77fzl7dcem8h7e"
+        );
+    }
+
+    #[test]
+    fn reject_invalid_amount() {
+        assert!(ibc_transfer(&msg_transfer("10.5")).unwrap().is_none());
+        assert!(ibc_transfer(&msg_transfer("")).unwrap().is_none());
+    }
+}
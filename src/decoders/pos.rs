@@ -0,0 +1,327 @@
+use async_trait::async_trait;
+use namada_sdk::{
+    borsh::BorshDeserialize,
+    tx::data::pos::{
+        BecomeValidator, Bond, CommissionChange, ConsensusKeyChange, MetaDataChange, Redelegation,
+        Withdraw,
+    },
+    types::address::Address,
+};
+use sqlx::Transaction;
+
+use super::{borsh_to_json, DecodedTx, TxDecoder};
+use crate::database::{Database, BONDS_TABLE_NAME, UNBONDS_TABLE_NAME};
+use crate::error::Error;
+
+/// Decodes the tx_bond or tx_unbond transactions, whose data have the
+/// same layout, and saves them to the `bonds` or `unbonds` table.
+pub(super) struct BondDecoder {
+    name: &'static str,
+    table: &'static str,
+}
+
+impl BondDecoder {
+    pub(super) const fn bond() -> Self {
+        Self {
+            name: "tx_bond",
+            table: BONDS_TABLE_NAME,
+        }
+    }
+
+    pub(super) const fn unbond() -> Self {
+        Self {
+            name: "tx_unbond",
+            table: UNBONDS_TABLE_NAME,
+        }
+    }
+}
+
+#[async_trait]
+impl TxDecoder for BondDecoder {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<serde_json::Value, Error> {
+        borsh_to_json::<Bond>(data)
+    }
+
+    async fn save(
+        &self,
+        tx: &DecodedTx<'_>,
+        sqlx_tx: &mut Transaction<'_, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        let bond = Bond::try_from_slice(tx.raw)?;
+
+        Database::save_bond(self.table, tx, &bond, sqlx_tx, network).await
+    }
+}
+
+/// Decodes the tx_redelegate transactions and saves them to the
+/// `redelegations` table.
+pub(super) struct RedelegationDecoder;
+
+#[async_trait]
+impl TxDecoder for RedelegationDecoder {
+    fn name(&self) -> &str {
+        "tx_redelegate"
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<serde_json::Value, Error> {
+        borsh_to_json::<Redelegation>(data)
+    }
+
+    async fn save(
+        &self,
+        tx: &DecodedTx<'_>,
+        sqlx_tx: &mut Transaction<'_, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        let redelegation = Redelegation::try_from_slice(tx.raw)?;
+
+        Database::save_redelegation(tx, &redelegation, sqlx_tx, network).await
+    }
+}
+
+/// Decodes the tx_withdraw transactions and saves them to the
+/// `withdrawals` table.
+pub(super) struct WithdrawDecoder;
+
+#[async_trait]
+impl TxDecoder for WithdrawDecoder {
+    fn name(&self) -> &str {
+        "tx_withdraw"
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<serde_json::Value, Error> {
+        borsh_to_json::<Withdraw>(data)
+    }
+
+    async fn save(
+        &self,
+        tx: &DecodedTx<'_>,
+        sqlx_tx: &mut Transaction<'_, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        let withdraw = Withdraw::try_from_slice(tx.raw)?;
+
+        Database::save_withdrawal(tx, &withdraw, sqlx_tx, network).await
+    }
+}
+
+/// Decodes the tx_claim_rewards transactions, whose data has the layout
+/// of a withdrawal, and saves them to the `reward_claims` table.
+pub(super) struct ClaimRewardsDecoder;
+
+#[async_trait]
+impl TxDecoder for ClaimRewardsDecoder {
+    fn name(&self) -> &str {
+        "tx_claim_rewards"
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<serde_json::Value, Error> {
+        borsh_to_json::<Withdraw>(data)
+    }
+
+    async fn save(
+        &self,
+        tx: &DecodedTx<'_>,
+        sqlx_tx: &mut Transaction<'_, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        let claim = Withdraw::try_from_slice(tx.raw)?;
+
+        Database::save_claim_rewards(tx, &claim, sqlx_tx, network).await
+    }
+}
+
+/// Decodes the tx_become_validator transactions and saves the initial
+/// state, commission, metadata and consensus key of the validator.
+pub(super) struct BecomeValidatorDecoder;
+
+#[async_trait]
+impl TxDecoder for BecomeValidatorDecoder {
+    fn name(&self) -> &str {
+        "tx_become_validator"
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<serde_json::Value, Error> {
+        borsh_to_json::<BecomeValidator>(data)
+    }
+
+    async fn save(
+        &self,
+        tx: &DecodedTx<'_>,
+        sqlx_tx: &mut Transaction<'_, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        let data = BecomeValidator::try_from_slice(tx.raw)?;
+        let validator = data.address.to_string();
+        let max_change = data.max_commission_rate_change.to_string();
+
+        Database::save_validator_state(tx, &validator, "become_validator", sqlx_tx, network)
+            .await?;
+        Database::save_validator_commission(
+            tx,
+            &validator,
+            &data.commission_rate.to_string(),
+            Some(&max_change),
+            sqlx_tx,
+            network,
+        )
+        .await?;
+        Database::save_validator_metadata(tx, &validator, sqlx_tx, network).await?;
+        Database::save_validator_consensus_key(
+            tx,
+            &validator,
+            &data.consensus_key,
+            sqlx_tx,
+            network,
+        )
+        .await
+    }
+}
+
+/// Decodes the tx_change_validator_commission transactions and saves
+/// the new commission rate.
+pub(super) struct CommissionChangeDecoder;
+
+#[async_trait]
+impl TxDecoder for CommissionChangeDecoder {
+    fn name(&self) -> &str {
+        "tx_change_validator_commission"
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<serde_json::Value, Error> {
+        borsh_to_json::<CommissionChange>(data)
+    }
+
+    async fn save(
+        &self,
+        tx: &DecodedTx<'_>,
+        sqlx_tx: &mut Transaction<'_, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        let data = CommissionChange::try_from_slice(tx.raw)?;
+
+        Database::save_validator_commission(
+            tx,
+            &data.validator.to_string(),
+            &data.new_rate.to_string(),
+            None,
+            sqlx_tx,
+            network,
+        )
+        .await
+    }
+}
+
+/// Decodes the tx_change_validator_metadata transactions and saves the
+/// new metadata, and the new commission rate if any.
+pub(super) struct MetaDataChangeDecoder;
+
+#[async_trait]
+impl TxDecoder for MetaDataChangeDecoder {
+    fn name(&self) -> &str {
+        "tx_change_validator_metadata"
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<serde_json::Value, Error> {
+        borsh_to_json::<MetaDataChange>(data)
+    }
+
+    async fn save(
+        &self,
+        tx: &DecodedTx<'_>,
+        sqlx_tx: &mut Transaction<'_, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        let data = MetaDataChange::try_from_slice(tx.raw)?;
+        let validator = data.validator.to_string();
+
+        Database::save_validator_metadata(tx, &validator, sqlx_tx, network).await?;
+
+        if let Some(rate) = data.commission_rate {
+            Database::save_validator_commission(
+                tx,
+                &validator,
+                &rate.to_string(),
+                None,
+                sqlx_tx,
+                network,
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Decodes the tx_change_consensus_key transactions and saves the new
+/// consensus key.
+pub(super) struct ConsensusKeyChangeDecoder;
+
+#[async_trait]
+impl TxDecoder for ConsensusKeyChangeDecoder {
+    fn name(&self) -> &str {
+        "tx_change_consensus_key"
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<serde_json::Value, Error> {
+        borsh_to_json::<ConsensusKeyChange>(data)
+    }
+
+    async fn save(
+        &self,
+        tx: &DecodedTx<'_>,
+        sqlx_tx: &mut Transaction<'_, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        let data = ConsensusKeyChange::try_from_slice(tx.raw)?;
+
+        Database::save_validator_consensus_key(
+            tx,
+            &data.validator.to_string(),
+            &data.consensus_key,
+            sqlx_tx,
+            network,
+        )
+        .await
+    }
+}
+
+/// Decodes the transactions whose data is the address of a validator
+/// changing its state, like tx_deactivate_validator, and saves the change.
+pub(super) struct ValidatorStateDecoder {
+    name: &'static str,
+}
+
+impl ValidatorStateDecoder {
+    pub(super) const fn new(name: &'static str) -> Self {
+        Self { name }
+    }
+}
+
+#[async_trait]
+impl TxDecoder for ValidatorStateDecoder {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<serde_json::Value, Error> {
+        borsh_to_json::<Address>(data)
+    }
+
+    async fn save(
+        &self,
+        tx: &DecodedTx<'_>,
+        sqlx_tx: &mut Transaction<'_, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        let validator = Address::try_from_slice(tx.raw)?;
+        let action = self.name.strip_prefix("tx_").unwrap_or(self.name);
+
+        Database::save_validator_state(tx, &validator.to_string(), action, sqlx_tx, network).await
+    }
+}
@@ -0,0 +1,33 @@
+use async_trait::async_trait;
+use namada_sdk::{borsh::BorshDeserialize, types::token::Transfer};
+use sqlx::Transaction;
+
+use super::{borsh_to_json, DecodedTx, TxDecoder};
+use crate::database::Database;
+use crate::error::Error;
+
+/// Decodes the tx_transfer transactions and saves them to the
+/// `transfers` table.
+pub(super) struct TransferDecoder;
+
+#[async_trait]
+impl TxDecoder for TransferDecoder {
+    fn name(&self) -> &str {
+        "tx_transfer"
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<serde_json::Value, Error> {
+        borsh_to_json::<Transfer>(data)
+    }
+
+    async fn save(
+        &self,
+        tx: &DecodedTx<'_>,
+        sqlx_tx: &mut Transaction<'_, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        let transfer = Transfer::try_from_slice(tx.raw)?;
+
+        Database::save_transfer(tx, &transfer, sqlx_tx, network).await
+    }
+}
@@ -51,8 +51,14 @@ pub enum Error {
 
     #[error("serde_json error: {0}")]
     SerdeJsonError(#[from] serde_json::Error),
+    #[error("Stored chain does not match the node chain at height {0}")]
+    ChainMismatch(u64),
+    #[error("Missing block results for height {0}")]
+    MissingBlockResults(u64),
     #[error("Invalid checksum data")]
     InvalidChecksum,
+    #[error("Invalid genesis validator: {0}")]
+    InvalidGenesisValidator(String),
     #[error("Unknow error: {0}")]
     Generic(Box<dyn StdError + Send>),
     #[error("ParseInt error")]
@@ -0,0 +1,72 @@
+use futures::stream::StreamExt;
+use futures_util::pin_mut;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tracing::{info, instrument};
+
+use super::backoff::Backoff;
+use super::rpc::RpcPool;
+use super::{blocks_stream, utils};
+use crate::checksums::Checksums;
+use crate::config::IndexerConfig;
+use crate::database::Database;
+use crate::decoders::DecoderRegistry;
+use crate::error::Error;
+
+/// Indexes the blocks at `heights`, which are expected to be missing from
+/// the database and sorted in ascending order.
+///
+/// This runs alongside the live indexing, it does not depend on the last
+/// saved height and every block is checked against its stored neighbours
+/// so a backfilled range can not break the hash chain. The `checksums`
+/// are read for every block, a reload made meanwhile is used right away.
+#[instrument(name = "Indexer::backfill", skip_all)]
+pub(super) async fn backfill(
+    db: &Database,
+    rpc: &RpcPool,
+    config: &IndexerConfig,
+    chain_name: &str,
+    checksums: &RwLock<Checksums>,
+    decoders: &DecoderRegistry,
+    heights: Vec<u32>,
+) -> Result<(), Error> {
+    let (Some(first), Some(last)) = (heights.first(), heights.last()) else {
+        return Ok(());
+    };
+
+    info!(
+        "Backfilling {} blocks between {} and {}",
+        heights.len(),
+        first,
+        last
+    );
+
+    let backoff = Backoff::new(
+        Duration::from_millis(config.backoff_min_ms),
+        Duration::from_millis(config.backoff_max_ms),
+    );
+
+    let stream = blocks_stream(
+        heights.into_iter().map(u64::from),
+        chain_name,
+        rpc,
+        &backoff,
+        config.fetch_concurrency,
+    );
+    pin_mut!(stream);
+
+    while let Some((block, block_results)) = stream.next().await {
+        let height = block.header.height.value() as u32;
+
+        utils::check_neighbours(db, &block).await?;
+
+        db.save_block(&block, &block_results, &*checksums.read().await, decoders)
+            .await?;
+
+        metrics::decrement_gauge!(crate::INDEXER_MISSING_BLOCKS, 1.0);
+
+        info!("Block: {} backfilled", height);
+    }
+
+    Ok(())
+}
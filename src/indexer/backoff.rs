@@ -0,0 +1,87 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Exponential back-off shared by all the concurrent requests to the node.
+///
+/// Every failed request doubles the time to wait before the next retry
+/// up to `max`, a successful request resets it, so a struggling node
+/// gets some room while a healthy one is queried at full speed.
+#[derive(Debug)]
+pub struct Backoff {
+    // current delay in milliseconds, 0 means no failures so far
+    delay: AtomicU64,
+    min: u64,
+    max: u64,
+}
+
+impl Backoff {
+    pub fn new(min: Duration, max: Duration) -> Self {
+        let min = min.as_millis() as u64;
+        let max = max.as_millis() as u64;
+
+        Self {
+            delay: AtomicU64::new(0),
+            min,
+            max: max.max(min),
+        }
+    }
+
+    /// Resets the delay after a successful request.
+    pub fn success(&self) {
+        self.delay.store(0, Ordering::Relaxed);
+    }
+
+    /// Registers a failed request and returns the time to wait before retrying.
+    pub fn failure(&self) -> Duration {
+        let next = |d: u64| {
+            if d == 0 {
+                self.min
+            } else {
+                d.saturating_mul(2).min(self.max)
+            }
+        };
+
+        // fetch_update returns the value before the update
+        let previous = self
+            .delay
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |d| Some(next(d)))
+            .unwrap_or_else(|d| d);
+
+        Duration::from_millis(next(previous))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delay_doubles_up_to_max() {
+        let backoff = Backoff::new(Duration::from_millis(100), Duration::from_millis(1000));
+
+        let delays: Vec<u64> = (0..6)
+            .map(|_| backoff.failure().as_millis() as u64)
+            .collect();
+
+        assert_eq!(delays, vec![100, 200, 400, 800, 1000, 1000]);
+    }
+
+    #[test]
+    fn success_resets_delay() {
+        let backoff = Backoff::new(Duration::from_millis(100), Duration::from_millis(1000));
+
+        backoff.failure();
+        backoff.failure();
+        backoff.success();
+
+        assert_eq!(backoff.failure(), Duration::from_millis(100));
+    }
+
+    #[test]
+    fn max_is_at_least_min() {
+        let backoff = Backoff::new(Duration::from_millis(500), Duration::from_millis(100));
+
+        assert_eq!(backoff.failure(), Duration::from_millis(500));
+        assert_eq!(backoff.failure(), Duration::from_millis(500));
+    }
+}
@@ -0,0 +1,231 @@
+use serde::de::DeserializeOwned;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use tendermint::block::Block;
+use tendermint_rpc::endpoint::block_results;
+use tracing::{info, instrument};
+
+use super::{utils, BlockInfo};
+use crate::checksums::Checksums;
+use crate::database::Database;
+use crate::decoders::DecoderRegistry;
+use crate::error::Error;
+
+type JsonIter<T> = Box<dyn Iterator<Item = Result<T, Error>> + Send>;
+
+/// Reads values from a JSON file holding either a single array
+/// (like the test vectors generated by `examples/generate.rs`)
+/// or one value per line (NDJSON).
+///
+/// NDJSON files are streamed, arrays are loaded at once.
+fn read_json<T>(path: &Path) -> Result<JsonIter<T>, Error>
+where
+    T: DeserializeOwned + Send + 'static,
+{
+    let mut reader = BufReader::new(File::open(path)?);
+
+    // peek at the first meaningful byte to find out the format
+    let is_array = loop {
+        let buf = reader.fill_buf()?;
+        match buf.iter().position(|b| !b.is_ascii_whitespace()) {
+            Some(pos) => {
+                let is_array = buf[pos] == b'[';
+                reader.consume(pos);
+                break is_array;
+            }
+            None if buf.is_empty() => return Ok(Box::new(std::iter::empty())),
+            None => {
+                let len = buf.len();
+                reader.consume(len);
+            }
+        }
+    };
+
+    if is_array {
+        let values: Vec<T> = serde_json::from_reader(reader)?;
+        return Ok(Box::new(values.into_iter().map(Ok)));
+    }
+
+    let values = serde_json::Deserializer::from_reader(reader)
+        .into_iter::<T>()
+        .map(|v| v.map_err(Error::from));
+
+    Ok(Box::new(values))
+}
+
+/// Blocks and their results read from archive files instead of a node.
+pub struct FileSource {
+    blocks: JsonIter<Block>,
+    block_results: JsonIter<block_results::Response>,
+}
+
+impl FileSource {
+    pub fn open(blocks: &Path, block_results: &Path) -> Result<Self, Error> {
+        Ok(Self {
+            blocks: read_json(blocks)?,
+            block_results: read_json(block_results)?,
+        })
+    }
+}
+
+impl Iterator for FileSource {
+    type Item = Result<BlockInfo, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let block = match self.blocks.next()? {
+            Ok(block) => block,
+            Err(e) => return Some(Err(e)),
+        };
+        let height = block.header.height.value();
+
+        // both files are expected to hold the same heights in the same order
+        let block_results = match self.block_results.next() {
+            Some(Ok(res)) if res.height.value() == height => res,
+            Some(Err(e)) => return Some(Err(e)),
+            _ => return Some(Err(Error::MissingBlockResults(height))),
+        };
+
+        Some(Ok((block, block_results)))
+    }
+}
+
+/// Saves the blocks read from `source`, checking each one fits
+/// between the blocks already stored. Blocks already stored are skipped
+/// as long as they match the archived ones.
+#[instrument(name = "Indexer::ingest", skip_all)]
+pub(super) async fn ingest(
+    db: &Database,
+    source: FileSource,
+    checksums: &Checksums,
+    decoders: &DecoderRegistry,
+) -> Result<(), Error> {
+    let mut count = 0;
+
+    for res in source {
+        let (block, block_results) = res?;
+        let height = block.header.height.value() as u32;
+
+        if let Some(stored_id) = db.block_id_by_height(height).await? {
+            if stored_id.as_slice() != block.header.hash().as_bytes() {
+                return Err(Error::ChainMismatch(height as u64));
+            }
+            continue;
+        }
+
+        utils::check_neighbours(db, &block).await?;
+
+        db.save_block(&block, &block_results, checksums, decoders)
+            .await?;
+        count += 1;
+
+        info!("Block: {} ingested", height);
+    }
+
+    info!("{} blocks ingested", count);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn vector(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join(name)
+    }
+
+    // a file of the temporary directory, removed once dropped
+    struct Fixture(PathBuf);
+
+    impl std::ops::Deref for Fixture {
+        type Target = Path;
+
+        fn deref(&self) -> &Path {
+            &self.0
+        }
+    }
+
+    impl Drop for Fixture {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    // writes `content` to a file of the temporary directory, named after
+    // the process and a counter so concurrent test runs don't share it
+    fn fixture(name: &str, content: &str) -> Fixture {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let path = std::env::temp_dir().join(format!(
+            "namadexer_{}_{}_{}",
+            std::process::id(),
+            COUNT.fetch_add(1, Ordering::Relaxed),
+            name
+        ));
+        std::fs::write(&path, content).unwrap();
+        Fixture(path)
+    }
+
+    fn read_all(path: &Path) -> Result<Vec<u32>, Error> {
+        read_json::<u32>(path)?.collect()
+    }
+
+    #[test]
+    fn read_json_array_and_lines() {
+        let array = fixture("array.json", "\n  [1, 2, 3]\n");
+        let lines = fixture("lines.ndjson", "1\n2\n3\n");
+        let empty = fixture("empty.json", " \n");
+
+        assert_eq!(read_all(&array).unwrap(), vec![1, 2, 3]);
+        assert_eq!(read_all(&lines).unwrap(), vec![1, 2, 3]);
+        assert!(read_all(&empty).unwrap().is_empty());
+    }
+
+    #[test]
+    fn read_json_errors() {
+        let malformed = fixture("malformed.ndjson", "1\n{\n");
+
+        assert!(matches!(
+            read_all(&malformed),
+            Err(Error::SerdeJsonError(_))
+        ));
+        assert!(matches!(
+            read_all(Path::new("/nonexistent/blocks.json")),
+            Err(Error::IO(_))
+        ));
+    }
+
+    #[test]
+    fn file_source_reads_test_vectors() {
+        let source = FileSource::open(
+            &vector("blocks_vector.json"),
+            &vector("block_results_vector.json"),
+        )
+        .unwrap();
+
+        let heights = source
+            .map(|res| res.map(|(block, _)| block.header.height.value()))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(heights.len(), 301);
+        assert_eq!(heights[0], 1);
+        assert_eq!(heights[300], 301);
+    }
+
+    #[test]
+    fn file_source_missing_block_results() {
+        let block_results = fixture("block_results.json", "[]");
+        let mut source = FileSource::open(&vector("blocks_vector.json"), &block_results).unwrap();
+
+        assert!(matches!(
+            source.next(),
+            Some(Err(Error::MissingBlockResults(1)))
+        ));
+    }
+}
@@ -0,0 +1,68 @@
+use futures::stream::StreamExt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tendermint_rpc::event::EventData;
+use tendermint_rpc::query::EventType;
+use tendermint_rpc::{SubscriptionClient, WebSocketClient};
+use tokio::sync::mpsc::Sender;
+use tracing::{info, instrument};
+
+use super::backoff::Backoff;
+use super::rpc::RpcPool;
+use super::{get_block, BlockInfo};
+use crate::error::Error;
+
+/// Subscribes to the `NewBlock` events of the node at `addr` and sends every
+/// block up to the notified height, starting at `next_height`.
+///
+/// Blocks are still retrieved through the [RpcPool] as block results are not
+/// part of the event, this also fills any gap left by lost events.
+///
+/// Returns once the subscription is closed or the consumer is gone.
+#[instrument(name = "Indexer::follow_new_blocks", skip_all)]
+pub(super) async fn follow_new_blocks(
+    addr: &str,
+    next_height: &mut u64,
+    chain_name: &str,
+    rpc: &RpcPool,
+    backoff: &Backoff,
+    tx: &Sender<BlockInfo>,
+    shutdown: &AtomicBool,
+) -> Result<(), Error> {
+    let (client, driver) = WebSocketClient::new(addr).await?;
+    let driver_handle = tokio::spawn(async move { driver.run().await });
+
+    let res = async {
+        let mut subscription = client.subscribe(EventType::NewBlock.into()).await?;
+        info!("Subscribed to new blocks on {}", addr);
+
+        while let Some(event) = subscription.next().await {
+            let height = match event?.data {
+                EventData::NewBlock {
+                    block: Some(block), ..
+                }
+                | EventData::LegacyNewBlock {
+                    block: Some(block), ..
+                } => block.header.height.value(),
+                _ => continue,
+            };
+
+            while *next_height <= height {
+                if shutdown.load(Ordering::Relaxed) || tx.is_closed() {
+                    return Ok(());
+                }
+
+                let block = get_block(*next_height as u32, chain_name, rpc, backoff).await;
+                tx.send(block).await?;
+                *next_height += 1;
+            }
+        }
+
+        Ok::<(), Error>(())
+    }
+    .await;
+
+    client.close()?;
+    _ = driver_handle.await;
+
+    res
+}
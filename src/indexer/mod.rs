@@ -1,8 +1,9 @@
 use crate::config::IndexerConfig;
-use crate::utils::load_checksums;
 use futures::stream::StreamExt;
 use futures_util::pin_mut;
 use futures_util::Stream;
+use sqlx::Row as TRow;
+use std::path::Path;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
@@ -13,12 +14,23 @@ use tendermint_rpc::endpoint::block_results;
 use tendermint_rpc::{self, Client, HttpClient};
 use tokio::sync::mpsc::Receiver;
 use tokio::sync::mpsc::Sender;
+use tokio::sync::RwLock;
 use tokio::task::JoinHandle;
 use tracing::{info, instrument};
 
+mod backfill;
+mod backoff;
+mod file_source;
+mod live;
+mod rpc;
 pub mod utils;
 
+use backoff::Backoff;
+use file_source::FileSource;
+use rpc::RpcPool;
+
 use super::database::Database;
+use super::decoders::DecoderRegistry;
 use super::error::Error;
 
 // Time to wait between unsuccesfull calls to http.get_block
@@ -30,15 +42,29 @@ const WAIT_FOR_BLOCK: u64 = 10;
 // processes.
 const MAX_BLOCKS_IN_CHANNEL: usize = 100;
 
+// Number of blocks fetched by polling when the new blocks
+// subscription drops, before trying to subscribe again.
+const POLLING_FALLBACK_BLOCKS: usize = 10;
+
+// Number of blocks whose transactions are decoded again at once.
+const REDECODE_BATCH_SIZE: u32 = 1000;
+
 // Block info required to be saved
 type BlockInfo = (Block, block_results::Response);
 
-#[instrument(skip(client))]
-async fn get_block(block_height: u32, chain_name: &str, client: &HttpClient) -> BlockInfo {
+#[instrument(skip(rpc, backoff))]
+async fn get_block(
+    block_height: u32,
+    chain_name: &str,
+    rpc: &RpcPool,
+    backoff: &Backoff,
+) -> BlockInfo {
     loop {
         let height = Height::from(block_height);
         tracing::trace!(message = "Requesting block: ", block_height);
 
+        let (endpoint, client) = rpc.client();
+
         let instant = tokio::time::Instant::now();
 
         let response = client.block(block_height).await;
@@ -68,11 +94,15 @@ async fn get_block(block_height: u32, chain_name: &str, client: &HttpClient) ->
 
                 // If we successfully retrieved a block we want to get the block result.
                 // It is used to know if a transaction has been successfully or not.
-                let block_results = get_block_results(height, client).await;
+                let block_results = get_block_results(height, client, backoff).await;
 
                 if let Ok(br) = block_results {
+                    rpc.report_success(endpoint);
+                    backoff.success();
                     return (resp.block, br);
                 }
+
+                rpc.report_failure(endpoint);
             }
 
             Err(err) => {
@@ -84,6 +114,19 @@ async fn get_block(block_height: u32, chain_name: &str, client: &HttpClient) ->
                 );
 
                 match &err.0 {
+                    // another endpoint already has this block, so this
+                    // one is lagging behind.
+                    tendermint_rpc::error::ErrorDetail::Response(e)
+                        if rpc.tip() >= block_height as u64 =>
+                    {
+                        tracing::warn!(
+                            "Failed to retreive block at height {} from a lagging endpoint. (REASON : {})",
+                            block_height,
+                            e,
+                        );
+                        rpc.report_failure(endpoint);
+                        tokio::time::sleep(backoff.failure()).await;
+                    }
                     tendermint_rpc::error::ErrorDetail::Response(e) => {
                         tracing::warn!(
                                 "Failed to retreive block at height {}. Trying again in 10 seconds. (REASON : {})",
@@ -100,6 +143,8 @@ async fn get_block(block_height: u32, chain_name: &str, client: &HttpClient) ->
                             block_height,
                             e,
                         );
+                        rpc.report_failure(endpoint);
+                        tokio::time::sleep(backoff.failure()).await;
                     }
                     _ => {
                         tracing::warn!(
@@ -107,6 +152,8 @@ async fn get_block(block_height: u32, chain_name: &str, client: &HttpClient) ->
                             block_height,
                             err.detail(),
                         );
+                        rpc.report_failure(endpoint);
+                        tokio::time::sleep(backoff.failure()).await;
                     }
                 }
             }
@@ -114,10 +161,11 @@ async fn get_block(block_height: u32, chain_name: &str, client: &HttpClient) ->
     }
 }
 
-#[instrument(name = "Indexer::block_results", skip(client))]
+#[instrument(name = "Indexer::block_results", skip(client, backoff))]
 async fn get_block_results(
     block_height: Height,
     client: &HttpClient,
+    backoff: &Backoff,
 ) -> Result<block_results::Response, Error> {
     let response = client.block_results(block_height).await;
 
@@ -141,6 +189,7 @@ async fn get_block_results(
                         block_height,
                         e,
                     );
+                    tokio::time::sleep(backoff.failure()).await;
                 }
                 _ => {
                     tracing::warn!(
@@ -148,6 +197,7 @@ async fn get_block_results(
                         block_height,
                         err.detail(),
                     );
+                    tokio::time::sleep(backoff.failure()).await;
                 }
             }
 
@@ -156,15 +206,20 @@ async fn get_block_results(
     }
 }
 
+/// Returns a stream of the blocks at `heights`, up to `concurrency` blocks
+/// are requested at the same time but they are yielded in order.
 #[allow(clippy::let_with_type_underscore)]
-#[instrument(name = "Indexer::blocks_stream", skip(client, block))]
+#[instrument(name = "Indexer::blocks_stream", skip(rpc, heights, backoff))]
 fn blocks_stream<'a>(
-    block: u64,
+    heights: impl Iterator<Item = u64> + 'a,
     chain_name: &'a str,
-    client: &'a HttpClient,
+    rpc: &'a RpcPool,
+    backoff: &'a Backoff,
+    concurrency: usize,
 ) -> impl Stream<Item = BlockInfo> + 'a {
-    futures::stream::iter(block..)
-        .then(move |i| async move { get_block(i as u32, chain_name, client).await })
+    futures::stream::iter(heights)
+        .map(move |i| get_block(i as u32, chain_name, rpc, backoff))
+        .buffered(concurrency.max(1))
 }
 
 /// Start the indexer service blocking current thread.
@@ -174,11 +229,14 @@ fn blocks_stream<'a>(
 ///
 /// `config` The configuration containing required information used to connect to namada node
 /// to retrieve blocks from.
+///
+/// `decoders` The [decoders](DecoderRegistry) of the transactions data.
 pub async fn start_indexing(
     db: Database,
     config: &IndexerConfig,
     chain_name: &str,
     create_index: bool,
+    decoders: &DecoderRegistry,
 ) -> Result<(), Error> {
     info!("***** Starting indexer *****");
 
@@ -189,6 +247,19 @@ pub async fn start_indexing(
      ********************/
 
     let mut current_height = utils::get_start_height(&db).await?;
+
+    // Nothing indexed yet, the node might be pruned so start
+    // at the configured height.
+    if current_height == 1 && config.end_height.is_none() {
+        current_height = config.start_height.unwrap_or(1).max(1);
+    }
+
+    // The backfilled range is indexed by another task,
+    // live indexing starts above it.
+    if let Some(end_height) = config.end_height {
+        current_height = current_height.max(end_height + 1);
+    }
+
     info!("Starting at height : {}", &current_height);
 
     // check if indexes has been created in the database
@@ -200,7 +271,9 @@ pub async fn start_indexing(
      *
      ********************/
 
-    let checksums_map = load_checksums()?;
+    // shared with the backfill task so it picks up the reloads
+    let checksums = Arc::new(RwLock::new(utils::refresh_checksums(&db).await?));
+    let mut checksums_loaded_at = tokio::time::Instant::now();
 
     /********************
      *
@@ -208,62 +281,269 @@ pub async fn start_indexing(
      *
      ********************/
 
-    // Connect to a RPC
-    info!("Connecting to {}", config.tendermint_addr);
-    let client = HttpClient::new(config.tendermint_addr.as_str())?;
+    // Connect to the RPC endpoints
+    let rpc = RpcPool::new(config)?;
+    let tip = rpc.refresh().await?;
+
+    // keep track of endpoints lagging behind while indexing
+    let _health_check = rpc.spawn_health_check();
+
+    /********************
+     *
+     *  Verify stored chain
+     *
+     ********************/
+
+    // Make sure the last blocks we stored are still part of the
+    // chain served by the node, a node swap or a corrupted database
+    // would otherwise end up in a stitched-together chain.
+    current_height = utils::rollback_to_common_block(&db, &rpc, current_height - 1).await?;
+
+    /********************
+     *
+     *  Backfill
+     *
+     ********************/
+
+    // blocks missing bellow the last one saved
+    let mut heights = utils::find_gaps(&db).await?;
+
+    if let Some(end_height) = config.end_height {
+        let start_height = config.start_height.unwrap_or(1).max(1);
+        heights.extend(db.missing_heights(start_height, end_height).await?);
+        heights.sort_unstable();
+        heights.dedup();
+    }
+
+    metrics::gauge!(crate::INDEXER_MISSING_BLOCKS, heights.len() as f64);
+
+    if !heights.is_empty() {
+        let db = db.clone();
+        let rpc = rpc.clone();
+        let config = config.clone();
+        let chain_name = chain_name.to_string();
+        let checksums = checksums.clone();
+        let decoders = decoders.clone();
+
+        tokio::spawn(async move {
+            let res = backfill::backfill(
+                &db,
+                &rpc,
+                &config,
+                &chain_name,
+                &checksums,
+                &decoders,
+                heights,
+            )
+            .await;
+
+            match res {
+                Ok(()) => info!("Backfill done"),
+                Err(e) => tracing::error!("Backfill stopped: {e}"),
+            }
+        });
+    }
 
     /********************
      *
      *  Start indexing
      *
      ********************/
-    let latest_block = client.latest_block().await?;
-    info!("Current block tip {}", &latest_block.block.header.height);
+    info!("Current block tip {}", tip);
+
+    loop {
+        let shutdown = Arc::new(AtomicBool::new(false));
+
+        let producer_shutdown = shutdown.clone();
+
+        // Spaw block producer task, this could speed up saving blocks
+        // because it does not need to wait for database to finish saving a block.
+        let (mut rx, producer_handler) = spawn_block_producer(
+            current_height as _,
+            chain_name,
+            rpc.clone(),
+            config,
+            producer_shutdown,
+        );
+
+        // Id of the last saved block, every new block must point to it.
+        let mut parent_id = db.block_id_by_height(current_height - 1).await?;
+        let mut fork_detected = false;
+
+        // Block consumer that stores block into the database
+        while let Some(block) = rx.recv().await {
+            if !utils::extends(&block.0, parent_id.as_deref()) {
+                tracing::warn!(
+                    "Block {} does not extend the last saved block",
+                    block.0.header.height
+                );
+                fork_detected = true;
+                break;
+            }
+
+            // pick up checksums added since the last load, like the ones
+            // of a wasm upgraded through governance.
+            if config.checksums_reload_interval > 0
+                && checksums_loaded_at.elapsed()
+                    >= Duration::from_secs(config.checksums_reload_interval)
+            {
+                match utils::refresh_checksums(&db).await {
+                    Ok(c) => *checksums.write().await = c,
+                    Err(e) => tracing::warn!("Failed to reload checksums: {e}"),
+                }
+                checksums_loaded_at = tokio::time::Instant::now();
+            }
+
+            // block is now the block info and the block results
+            if let Err(e) = db
+                .save_block(&block.0, &block.1, &*checksums.read().await, decoders)
+                .await
+            {
+                // shutdown producer task
+                shutdown.store(true, Ordering::Relaxed);
+                tracing::error!(
+                    "Closing block producer task due to an error saving last block: {e}"
+                );
+
+                // propagate the error
+                return Err(e);
+            }
+
+            info!("Block: {} saved", block.0.header.height.value());
+
+            parent_id = Some(block.0.header.hash().as_bytes().to_vec());
+
+            // create indexes if they have not been created yet
+            if !has_indexes && tip == current_height as u64 {
+                info!("We are synced!");
 
-    let shutdown = Arc::new(AtomicBool::new(false));
+                if create_index {
+                    info!("Creating indexes");
+                    db.create_indexes().await?;
 
-    let producer_shutdown = shutdown.clone();
+                    info!("Indexing done");
+                }
+            }
 
-    // Spaw block producer task, this could speed up saving blocks
-    // because it does not need to wait for database to finish saving a block.
-    let (mut rx, producer_handler) =
-        spawn_block_producer(current_height as _, chain_name, client, producer_shutdown);
+            current_height += 1;
+        }
 
-    // Block consumer that stores block into the database
-    while let Some(block) = rx.recv().await {
-        // block is now the block info and the block results
-        if let Err(e) = db.save_block(&block.0, &block.1, &checksums_map).await {
-            // shutdown producer task
+        if fork_detected {
+            // blocks already queued belong to the old chain,
+            // stop the producer without waiting for it.
             shutdown.store(true, Ordering::Relaxed);
-            tracing::error!("Closing block producer task due to an error saving last block: {e}");
+            producer_handler.abort();
+
+            current_height = utils::rollback_to_common_block(&db, &rpc, current_height - 1).await?;
+            info!("Resuming indexing at height {}", current_height);
 
-            // propagate the error
-            return Err(e);
+            continue;
         }
 
-        info!("Block: {} saved", block.0.header.height.value());
+        // propagate any error from the block producer
+        // like failing to connect to namada node for any reason
+        // and so on.
+        producer_handler.await??;
 
-        let height = Height::from(current_height);
+        return Ok(());
+    }
+}
 
-        // create indexes if they have not been created yet
-        if !has_indexes && latest_block.block.header.height == height {
-            info!("We are synced!");
+/// Re-fetches and saves the blocks missing between the lowest and the
+/// highest height stored, then returns.
+#[instrument(skip(db, config, decoders))]
+pub async fn repair(
+    db: Database,
+    config: &IndexerConfig,
+    chain_name: &str,
+    decoders: &DecoderRegistry,
+) -> Result<(), Error> {
+    let heights = utils::find_gaps(&db).await?;
+    metrics::gauge!(crate::INDEXER_MISSING_BLOCKS, heights.len() as f64);
 
-            if create_index {
-                info!("Creating indexes");
-                db.create_indexes().await?;
+    if heights.is_empty() {
+        info!("No missing blocks");
+        return Ok(());
+    }
 
-                info!("Indexing done");
-            }
+    let checksums = RwLock::new(utils::refresh_checksums(&db).await?);
+
+    let rpc = RpcPool::new(config)?;
+    rpc.refresh().await?;
+
+    backfill::backfill(&db, &rpc, config, chain_name, &checksums, decoders, heights).await
+}
+
+/// Indexes blocks from archive files instead of a node, `blocks` holding
+/// `Block`s and `block_results` the matching `block_results::Response`s,
+/// either as a JSON array or one per line.
+#[instrument(skip(db, decoders))]
+pub async fn ingest(
+    db: Database,
+    blocks: &Path,
+    block_results: &Path,
+    decoders: &DecoderRegistry,
+) -> Result<(), Error> {
+    let checksums = utils::refresh_checksums(&db).await?;
+    let source = FileSource::open(blocks, block_results)?;
+
+    file_source::ingest(&db, source, &checksums, decoders).await
+}
+
+/// Decodes again the data of the transactions kept raw between the heights
+/// `from` and `to` (both included), only those of type `tx_type` if set,
+/// and updates it in place. `to` defaults to the last height stored.
+///
+/// Only the blocks indexed with `store_raw_txs` enabled can be decoded again.
+#[instrument(skip(db, decoders))]
+pub async fn redecode(
+    db: Database,
+    decoders: &DecoderRegistry,
+    tx_type: Option<&str>,
+    from: u32,
+    to: Option<u32>,
+) -> Result<(), Error> {
+    let checksums = utils::refresh_checksums(&db).await?;
+
+    let to = match to {
+        Some(to) => to,
+        None => {
+            let last: Option<i32> = db.get_last_height().await?.try_get("header_height")?;
+            last.unwrap_or_default() as u32
         }
+    };
 
-        current_height += 1;
+    let mut count = 0;
+    let mut start = from;
+
+    while start <= to {
+        let end = to.min(start.saturating_add(REDECODE_BATCH_SIZE - 1));
+
+        count += db
+            .redecode_txs(&checksums, decoders, tx_type, start, end)
+            .await?;
+
+        info!("Decoded blocks {} to {}", start, end);
+
+        if end == u32::MAX {
+            break;
+        }
+        start = end + 1;
     }
 
-    // propagate any error from the block producer
-    // like failing to connect to namada node for any reason
-    // and so on.
-    producer_handler.await??;
+    info!("{} transactions decoded again", count);
+
+    Ok(())
+}
+
+/// Saves the consensus keys of the validators created at genesis read from
+/// `input`, replacing the ones previously saved.
+#[instrument(skip(db))]
+pub async fn genesis(db: Database, input: &Path) -> Result<(), Error> {
+    let validators = crate::utils::load_genesis_validators(input)?;
+    db.save_genesis_validators(&validators).await?;
+
+    info!("{} genesis validators saved", validators.len());
 
     Ok(())
 }
@@ -271,29 +551,105 @@ pub async fn start_indexing(
 fn spawn_block_producer(
     current_height: u64,
     chain_name: &str,
-    client: HttpClient,
+    rpc: RpcPool,
+    config: &IndexerConfig,
     producer_shutdown: Arc<AtomicBool>,
 ) -> (Receiver<BlockInfo>, JoinHandle<Result<(), Error>>) {
     // Create a channel
     let (tx, rx): (Sender<BlockInfo>, Receiver<BlockInfo>) =
         tokio::sync::mpsc::channel(MAX_BLOCKS_IN_CHANNEL);
 
+    let backoff = Backoff::new(
+        Duration::from_millis(config.backoff_min_ms),
+        Duration::from_millis(config.backoff_max_ms),
+    );
+    let concurrency = config.fetch_concurrency;
+    let websocket_addr = config.websocket_addr.clone();
+
     // Spawn the task
     let chain_name = chain_name.to_string();
     let handler = tokio::spawn(async move {
-        let stream = blocks_stream(current_height as _, chain_name.as_str(), &client);
-        pin_mut!(stream);
+        let Some(websocket_addr) = websocket_addr else {
+            // Polling mode, keep asking for the next block.
+            let stream = blocks_stream(
+                current_height..,
+                chain_name.as_str(),
+                &rpc,
+                &backoff,
+                concurrency,
+            );
+            pin_mut!(stream);
+
+            while let Some(block) = stream.next().await {
+                if producer_shutdown.load(Ordering::Relaxed) {
+                    tracing::warn!("Block consumer closed, exiting producer");
+                    break;
+                }
 
-        while let Some(block) = stream.next().await {
-            if producer_shutdown.load(Ordering::Relaxed) {
+                tx.send(block).await?;
+            }
+
+            return Ok::<(), Error>(());
+        };
+
+        // Live mode, catch up with the tip and then wait for
+        // the node to notify us about new blocks.
+        let mut next_height = current_height;
+
+        loop {
+            let tip = rpc.refresh().await.unwrap_or_else(|_| rpc.tip());
+
+            let stream = blocks_stream(
+                next_height..=tip,
+                chain_name.as_str(),
+                &rpc,
+                &backoff,
+                concurrency,
+            );
+            pin_mut!(stream);
+
+            while let Some(block) = stream.next().await {
+                if producer_shutdown.load(Ordering::Relaxed) || tx.is_closed() {
+                    tracing::warn!("Block consumer closed, exiting producer");
+                    return Ok(());
+                }
+
+                tx.send(block).await?;
+                next_height += 1;
+            }
+
+            let res = live::follow_new_blocks(
+                &websocket_addr,
+                &mut next_height,
+                chain_name.as_str(),
+                &rpc,
+                &backoff,
+                &tx,
+                &producer_shutdown,
+            )
+            .await;
+
+            if producer_shutdown.load(Ordering::Relaxed) || tx.is_closed() {
                 tracing::warn!("Block consumer closed, exiting producer");
-                break;
+                return Ok(());
             }
 
-            tx.send(block).await?;
-        }
+            match res {
+                Ok(()) => tracing::warn!("New blocks subscription closed, polling for a while"),
+                Err(e) => {
+                    tracing::warn!("New blocks subscription failed, polling for a while: {e}")
+                }
+            }
 
-        Ok::<(), Error>(())
+            // Fall back to polling before subscribing again,
+            // get_block waits until each block is available.
+            for _ in 0..POLLING_FALLBACK_BLOCKS {
+                let block =
+                    get_block(next_height as u32, chain_name.as_str(), &rpc, &backoff).await;
+                tx.send(block).await?;
+                next_height += 1;
+            }
+        }
     });
 
     (rx, handler)
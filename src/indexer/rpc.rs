@@ -0,0 +1,288 @@
+use std::sync::atomic::{AtomicU32, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tendermint_rpc::{Client, HttpClient};
+use tokio::task::JoinHandle;
+use tracing::{info, instrument, warn};
+
+use crate::config::IndexerConfig;
+use crate::error::Error;
+use crate::INDEXER_RPC_FAILURE_COUNTER;
+
+// Time between two checks of the endpoints latest height.
+const HEALTH_CHECK_INTERVAL: u64 = 30;
+
+struct Endpoint {
+    addr: String,
+    client: HttpClient,
+    // consecutive failed requests, reset on success
+    failures: AtomicU32,
+    // latest height reported by the node on the last health check
+    latest_height: AtomicU64,
+}
+
+/// The set of RPC endpoints the indexer can get blocks from.
+///
+/// Endpoints failing `max_failures` times in a row or lagging more than
+/// `max_height_lag` blocks behind the highest known tip are considered
+/// unhealthy and skipped. Without load balancing the pool sticks to one
+/// endpoint and only fails over when it becomes unhealthy, otherwise
+/// requests are spread across all the healthy endpoints.
+#[derive(Clone)]
+pub struct RpcPool {
+    endpoints: Arc<Vec<Endpoint>>,
+    // endpoint in use when load balancing is disabled
+    active: Arc<AtomicUsize>,
+    // round robin counter used when load balancing
+    next: Arc<AtomicUsize>,
+    load_balance: bool,
+    max_failures: u32,
+    max_height_lag: u64,
+}
+
+impl RpcPool {
+    pub fn new(config: &IndexerConfig) -> Result<Self, Error> {
+        let mut addrs = vec![config.tendermint_addr.clone()];
+        for addr in config.tendermint_addrs.iter() {
+            if !addrs.contains(addr) {
+                addrs.push(addr.clone());
+            }
+        }
+
+        let endpoints = addrs
+            .into_iter()
+            .map(|addr| {
+                info!("Connecting to {}", addr);
+                let client = HttpClient::new(addr.as_str())?;
+
+                Ok(Endpoint {
+                    addr,
+                    client,
+                    failures: AtomicU32::new(0),
+                    latest_height: AtomicU64::new(0),
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(Self {
+            endpoints: Arc::new(endpoints),
+            active: Arc::new(AtomicUsize::new(0)),
+            next: Arc::new(AtomicUsize::new(0)),
+            load_balance: config.rpc_load_balance,
+            max_failures: config.rpc_max_failures.max(1),
+            max_height_lag: config.rpc_max_height_lag,
+        })
+    }
+
+    /// Returns the highest height known among all the endpoints.
+    pub fn tip(&self) -> u64 {
+        self.endpoints
+            .iter()
+            .map(|e| e.latest_height.load(Ordering::Relaxed))
+            .max()
+            .unwrap_or_default()
+    }
+
+    fn is_healthy(&self, endpoint: &Endpoint, tip: u64) -> bool {
+        endpoint.failures.load(Ordering::Relaxed) < self.max_failures
+            && tip.saturating_sub(endpoint.latest_height.load(Ordering::Relaxed))
+                <= self.max_height_lag
+    }
+
+    fn pick(&self) -> usize {
+        let tip = self.tip();
+
+        let healthy: Vec<usize> = (0..self.endpoints.len())
+            .filter(|i| self.is_healthy(&self.endpoints[*i], tip))
+            .collect();
+
+        if healthy.is_empty() {
+            // everything is failing, go for the endpoint with less failures
+            return (0..self.endpoints.len())
+                .min_by_key(|i| self.endpoints[*i].failures.load(Ordering::Relaxed))
+                .unwrap_or_default();
+        }
+
+        if self.load_balance {
+            let n = self.next.fetch_add(1, Ordering::Relaxed);
+            return healthy[n % healthy.len()];
+        }
+
+        let active = self.active.load(Ordering::Relaxed);
+        if healthy.contains(&active) {
+            return active;
+        }
+
+        let new_active = healthy[0];
+        warn!(
+            "RPC endpoint {} is unhealthy, failing over to {}",
+            self.endpoints[active].addr, self.endpoints[new_active].addr
+        );
+        self.active.store(new_active, Ordering::Relaxed);
+
+        new_active
+    }
+
+    /// Returns the client to use for the next request along with its index,
+    /// which has to be used to report the request outcome.
+    pub fn client(&self) -> (usize, &HttpClient) {
+        let idx = self.pick();
+        (idx, &self.endpoints[idx].client)
+    }
+
+    pub fn report_success(&self, idx: usize) {
+        self.endpoints[idx].failures.store(0, Ordering::Relaxed);
+    }
+
+    pub fn report_failure(&self, idx: usize) {
+        let endpoint = &self.endpoints[idx];
+        let failures = endpoint.failures.fetch_add(1, Ordering::Relaxed) + 1;
+
+        metrics::increment_counter!(INDEXER_RPC_FAILURE_COUNTER, "endpoint" => endpoint.addr.clone());
+
+        if failures == self.max_failures {
+            warn!(
+                "RPC endpoint {} failed {} times in a row, marking it unhealthy",
+                endpoint.addr, failures
+            );
+        }
+    }
+
+    /// Asks every endpoint for its latest height and returns the highest one.
+    /// Endpoints answering are given a fresh start.
+    #[instrument(name = "RpcPool::refresh", skip(self))]
+    pub async fn refresh(&self) -> Result<u64, Error> {
+        let mut last_error = None;
+
+        for (idx, endpoint) in self.endpoints.iter().enumerate() {
+            match endpoint.client.status().await {
+                Ok(status) => {
+                    endpoint.latest_height.store(
+                        status.sync_info.latest_block_height.value(),
+                        Ordering::Relaxed,
+                    );
+                    endpoint.failures.store(0, Ordering::Relaxed);
+                }
+                Err(e) => {
+                    warn!("Failed to get status from {}: {}", endpoint.addr, e);
+                    self.report_failure(idx);
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        // only fail if no endpoint answered
+        match last_error {
+            Some(e) if self.tip() == 0 => Err(Error::from(e)),
+            _ => Ok(self.tip()),
+        }
+    }
+
+    /// Spawns a task refreshing the endpoints health periodically,
+    /// the task is stopped once the returned guard is dropped.
+    pub fn spawn_health_check(&self) -> HealthCheck {
+        let pool = self.clone();
+
+        let handle = tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(Duration::from_secs(HEALTH_CHECK_INTERVAL)).await;
+                _ = pool.refresh().await;
+            }
+        });
+
+        HealthCheck(handle)
+    }
+}
+
+/// Guard stopping the health check task when dropped.
+pub struct HealthCheck(JoinHandle<()>);
+
+impl Drop for HealthCheck {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // endpoints at the given latest heights, unhealthy after 3 failures
+    // or when lagging more than 10 blocks behind.
+    fn pool(heights: &[u64], load_balance: bool) -> RpcPool {
+        let endpoints = heights
+            .iter()
+            .enumerate()
+            .map(|(i, height)| {
+                let addr = format!("http://127.0.0.{}:26657", i + 1);
+
+                Endpoint {
+                    client: HttpClient::new(addr.as_str()).unwrap(),
+                    addr,
+                    failures: AtomicU32::new(0),
+                    latest_height: AtomicU64::new(*height),
+                }
+            })
+            .collect();
+
+        RpcPool {
+            endpoints: Arc::new(endpoints),
+            active: Arc::new(AtomicUsize::new(0)),
+            next: Arc::new(AtomicUsize::new(0)),
+            load_balance,
+            max_failures: 3,
+            max_height_lag: 10,
+        }
+    }
+
+    #[test]
+    fn sticks_to_active_endpoint() {
+        let pool = pool(&[100, 100], false);
+
+        assert_eq!(pool.pick(), 0);
+        assert_eq!(pool.pick(), 0);
+    }
+
+    #[test]
+    fn fails_over_to_healthy_endpoint() {
+        let pool = pool(&[100, 100], false);
+
+        for _ in 0..3 {
+            pool.report_failure(0);
+        }
+        assert_eq!(pool.pick(), 1);
+
+        // the new active endpoint is kept once the old one recovers
+        pool.report_success(0);
+        assert_eq!(pool.pick(), 1);
+    }
+
+    #[test]
+    fn skips_lagging_endpoint() {
+        let pool = pool(&[50, 100], false);
+
+        assert_eq!(pool.pick(), 1);
+    }
+
+    #[test]
+    fn picks_least_failing_when_all_unhealthy() {
+        let pool = pool(&[100, 100, 100], false);
+
+        for (idx, failures) in [5, 3, 4].into_iter().enumerate() {
+            for _ in 0..failures {
+                pool.report_failure(idx);
+            }
+        }
+
+        assert_eq!(pool.pick(), 1);
+    }
+
+    #[test]
+    fn load_balances_over_healthy_endpoints() {
+        let pool = pool(&[100, 100, 20], true);
+
+        let picks: Vec<usize> = (0..4).map(|_| pool.pick()).collect();
+
+        assert_eq!(picks, vec![0, 1, 0, 1]);
+    }
+}
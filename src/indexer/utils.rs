@@ -1,8 +1,19 @@
+use crate::checksums::Checksums;
 use crate::database::Database;
 use crate::error::Error;
 use sqlx::Row as TRow;
+use tendermint::block::Block;
+use tendermint_rpc::Client;
 use tracing::instrument;
 
+use super::rpc::RpcPool;
+
+// Max number of blocks we are willing to roll back looking for
+// a block shared by the database and the node. A deeper
+// divergence most likely means we are connected to a node of another
+// chain, so we stop instead of wiping the database.
+const MAX_ROLLBACK_DEPTH: u32 = 1000;
+
 #[instrument(name = "Utils::get_start_height", skip(db))]
 pub async fn get_start_height(db: &Database) -> Result<u32, Error> {
     let last_row = db.get_last_height().await?;
@@ -26,3 +37,229 @@ pub async fn has_indexes(db: &Database) -> Result<bool, Error> {
 
     Ok(has_indexes)
 }
+
+/// Stores the checksums of the configured sources in the database and
+/// returns all the checksums known, including the ones added to the
+/// `tx_checksums` table by other means.
+///
+/// A source failing to load is not fatal as long as the database already
+/// has some checksums.
+#[instrument(name = "Utils::refresh_checksums", skip(db))]
+pub async fn refresh_checksums(db: &Database) -> Result<Checksums, Error> {
+    // reading the files and urls blocks, keep it off the runtime threads
+    match tokio::task::spawn_blocking(crate::utils::load_checksums).await? {
+        Ok(checksums) => db.save_checksums(&checksums).await?,
+        Err(e) => tracing::warn!("Failed to load checksums: {}", e),
+    }
+
+    let checksums = db.load_checksums().await?;
+    if checksums.is_empty() {
+        return Err(Error::InvalidChecksum);
+    }
+
+    tracing::info!("{} checksums loaded", checksums.len());
+
+    Ok(checksums)
+}
+
+/// Returns the heights missing between the lowest and the highest block
+/// stored, blocks that failed to be saved or were lost in a partial restore.
+///
+/// Gaps are reported in the logs as ranges.
+#[instrument(name = "Utils::find_gaps", skip(db))]
+pub async fn find_gaps(db: &Database) -> Result<Vec<u32>, Error> {
+    let first: Option<i32> = db.get_first_height().await?.try_get("header_height")?;
+    let last: Option<i32> = db.get_last_height().await?.try_get("header_height")?;
+
+    let (Some(first), Some(last)) = (first, last) else {
+        return Ok(vec![]);
+    };
+
+    let heights = db.missing_heights(first as u32, last as u32).await?;
+
+    for (start, end) in gap_ranges(&heights) {
+        tracing::warn!("Blocks {} to {} are missing", start, end);
+    }
+
+    Ok(heights)
+}
+
+/// Groups sorted heights into ranges of consecutive heights.
+pub fn gap_ranges(heights: &[u32]) -> Vec<(u32, u32)> {
+    let mut ranges: Vec<(u32, u32)> = vec![];
+
+    for h in heights {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == *h => *end = *h,
+            _ => ranges.push((*h, *h)),
+        }
+    }
+
+    ranges
+}
+
+/// Returns true if `block` points to `parent_id` as its previous block.
+///
+/// If `parent_id` is None there is nothing stored bellow this block
+/// to compare against, so the block is accepted.
+pub fn extends(block: &Block, parent_id: Option<&[u8]>) -> bool {
+    match (parent_id, block.header.last_block_id) {
+        (None, _) => true,
+        (Some(parent), Some(id)) => id.hash.as_bytes() == parent,
+        (Some(_), None) => false,
+    }
+}
+
+/// Makes sure `block` points to the block stored bellow it and that the
+/// block stored above it, if any, points to `block`.
+#[instrument(name = "Utils::check_neighbours", skip_all)]
+pub async fn check_neighbours(db: &Database, block: &Block) -> Result<(), Error> {
+    let height = block.header.height.value() as u32;
+
+    let parent_id = db.block_id_by_height(height - 1).await?;
+    if !extends(block, parent_id.as_deref()) {
+        return Err(Error::ChainMismatch(height as u64));
+    }
+
+    if let Some(child) = db.block_by_height(height + 1).await? {
+        let child_parent: Option<Vec<u8>> = child.try_get("header_last_block_id_hash")?;
+        if child_parent.as_deref() != Some(block.header.hash().as_bytes()) {
+            return Err(Error::ChainMismatch(height as u64 + 1));
+        }
+    }
+
+    Ok(())
+}
+
+/// Compares the blocks stored in the database, starting at `height` and going
+/// backwards, with the ones served by the node. Everything above the last block
+/// both agree on is rolled back.
+///
+/// Returns the height indexing has to resume from.
+#[instrument(name = "Utils::rollback_to_common_block", skip(db, rpc))]
+pub async fn rollback_to_common_block(
+    db: &Database,
+    rpc: &RpcPool,
+    height: u32,
+) -> Result<u32, Error> {
+    let mut resume_height = height + 1;
+
+    let mut h = height;
+    while h > 0 {
+        if height - h >= MAX_ROLLBACK_DEPTH {
+            tracing::error!(
+                "No common block found in the last {} blocks",
+                MAX_ROLLBACK_DEPTH
+            );
+            return Err(Error::ChainMismatch(h as u64));
+        }
+
+        // a missing block can not be compared, whatever is bellow it
+        // is left untouched.
+        let Some(stored_id) = db.block_id_by_height(h).await? else {
+            break;
+        };
+
+        let node_id = rpc.client().1.block(h).await?.block.header.hash();
+
+        if node_id.as_bytes() == stored_id.as_slice() {
+            break;
+        }
+
+        tracing::warn!(
+            "Block {} stored with id {} but node has {}",
+            h,
+            hex::encode(&stored_id),
+            node_id
+        );
+
+        resume_height = h;
+        h -= 1;
+    }
+
+    if resume_height <= height {
+        tracing::warn!(
+            "Chain diverges from the node at height {}, rolling back",
+            resume_height
+        );
+        db.rollback_from(resume_height).await?;
+    }
+
+    Ok(resume_height)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tendermint::block::Id as BlockId;
+
+    // first block of the chain the test vectors come from
+    const FIRST_BLOCK: &str = r#"{"header":{"version":{"block":"11","app":"0"},"chain_id":"shielded-expedition.b40d8e9055","height":"1","time":"2024-02-01T18:00:00Z","last_block_id":null,"last_commit_hash":"E3B0C44298FC1C149AFBF4C8996FB92427AE41E4649B934CA495991B7852B855","data_hash":"E3B0C44298FC1C149AFBF4C8996FB92427AE41E4649B934CA495991B7852B855","validators_hash":"7A1DD433DCD93BAA485341F4BB392055687EEC6C4A786F0C9DB9FD12E2B03DD8","next_validators_hash":"7A1DD433DCD93BAA485341F4BB392055687EEC6C4A786F0C9DB9FD12E2B03DD8","consensus_hash":"4B53C13521D2126D4E199E783963D0E8B2729F143EB01A7F3817A1D1E079193F","app_hash":"","last_results_hash":"E3B0C44298FC1C149AFBF4C8996FB92427AE41E4649B934CA495991B7852B855","evidence_hash":"E3B0C44298FC1C149AFBF4C8996FB92427AE41E4649B934CA495991B7852B855","proposer_address":"44092E5AEA6F6583644071066B42396C44749C48"},"data":{"txs":null},"evidence":{"evidence":null},"last_commit":null}"#;
+
+    const PARENT_HASH: &str = "08262ED93DD3208524633E6AEAE061D782EA8E532E6023015759ED325A0B2BDB";
+
+    fn block_with_parent(parent_hash: Option<&str>) -> Block {
+        let mut block: Block = serde_json::from_str(FIRST_BLOCK).unwrap();
+
+        block.header.last_block_id = parent_hash.map(|hash| {
+            serde_json::from_value::<BlockId>(serde_json::json!({
+                "hash": hash,
+                "parts": {"total": 1, "hash": hash},
+            }))
+            .unwrap()
+        });
+
+        block
+    }
+
+    #[test]
+    fn gap_ranges_without_gaps() {
+        assert!(gap_ranges(&[]).is_empty());
+    }
+
+    #[test]
+    fn gap_ranges_single_gap() {
+        assert_eq!(gap_ranges(&[5, 6, 7]), vec![(5, 7)]);
+        assert_eq!(gap_ranges(&[9]), vec![(9, 9)]);
+    }
+
+    #[test]
+    fn gap_ranges_at_boundaries() {
+        // gaps right after the first stored height (1) and right before
+        // the last one (20) start and end at the missing heights
+        let heights = [2, 3, 10, 18, 19];
+
+        assert_eq!(gap_ranges(&heights), vec![(2, 3), (10, 10), (18, 19)]);
+
+        // and at the ends of the range of heights
+        let heights = [0, 1, u32::MAX - 1, u32::MAX];
+
+        assert_eq!(gap_ranges(&heights), vec![(0, 1), (u32::MAX - 1, u32::MAX)]);
+    }
+
+    #[test]
+    fn extends_matching_parent() {
+        let block = block_with_parent(Some(PARENT_HASH));
+        let parent = hex::decode(PARENT_HASH).unwrap();
+
+        assert!(extends(&block, Some(&parent)));
+    }
+
+    #[test]
+    fn extends_mismatched_parent() {
+        let block = block_with_parent(Some(PARENT_HASH));
+        let parent = [0u8; 32];
+
+        assert!(!extends(&block, Some(&parent)));
+    }
+
+    #[test]
+    fn extends_first_block() {
+        let block = block_with_parent(None);
+
+        // nothing stored bellow it
+        assert!(extends(&block, None));
+        // a block without parent can't extend a stored block
+        assert!(!extends(&block, Some(&[0u8; 32])));
+    }
+}
@@ -1,5 +1,8 @@
+mod archive;
+pub mod checksums;
 mod config;
 pub mod database;
+pub mod decoders;
 mod error;
 mod indexer;
 pub(crate) mod queries;
@@ -10,11 +13,14 @@ pub mod utils;
 mod views;
 
 pub use crate::config::{
-    IndexerConfig, JaegerConfig, LogFormat, PrometheusConfig, ServerConfig, Settings,
+    CliSettings, IndexerConfig, JaegerConfig, LogFormat, PrometheusConfig, ServerConfig, Settings,
 };
+pub use archive::{export, import};
+pub use checksums::Checksums;
 pub use database::Database;
+pub use decoders::{DecodedTx, DecoderRegistry, TxDecoder};
 pub use error::Error;
-pub use indexer::start_indexing;
+pub use indexer::{genesis, ingest, redecode, repair, start_indexing};
 pub use server::{create_server, start_server, BlockInfo};
 pub use telemetry::{get_subscriber, init_subscriber, setup_logging};
 
@@ -26,5 +32,14 @@ const DB_SAVE_EVDS_DURATION: &str = "db_save_evidences_duration";
 const DB_SAVE_COMMIT_SIG_DURATION: &str = "db_save_commit_sig_duration";
 const INDEXER_LAST_SAVE_BLOCK_HEIGHT: &str = "indexer_last_save_block_height";
 const INDEXER_LAST_GET_BLOCK_HEIGHT: &str = "indexer_last_get_block_height";
+const INDEXER_ROLLBACK_COUNTER: &str = "indexer_rollback_count";
+const INDEXER_RPC_FAILURE_COUNTER: &str = "indexer_rpc_failure_count";
+const INDEXER_MISSING_BLOCKS: &str = "indexer_missing_blocks";
+const INDEXER_DECODE_FAILURE_COUNTER: &str = "indexer_tx_decode_failure_count";
 
 pub const MASP_ADDR: &str = "tnam1pcqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqzmefah";
+// The native token, bonded and paid as staking rewards, with the address
+// given to NAM at genesis.
+pub const NATIVE_TOKEN_ADDR: &str = "tnam1qxgfw7myv4dh0qna4hq0xdg6lx77fzl7dcem8h7e";
+// Decimal places of the native token amounts.
+const NATIVE_TOKEN_DECIMALS: u32 = 6;
@@ -0,0 +1,55 @@
+use crate::error::Error;
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgRow as Row;
+use sqlx::Row as TRow;
+
+/// The balance of an address for one token, in the smallest unit of
+/// the token.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct BalanceInfo {
+    pub token: String,
+    pub balance: String,
+}
+
+impl TryFrom<&Row> for BalanceInfo {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(Self {
+            token: row.try_get("token")?,
+            balance: row.try_get("balance")?,
+        })
+    }
+}
+
+/// A change of the balance of an address.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct BalanceChangeInfo {
+    /// The block the change has been recorded in.
+    #[serde(with = "hex::serde")]
+    pub block_id: Vec<u8>,
+    pub height: i32,
+    /// The transaction, or the wrapper for fees, causing the change.
+    #[serde(with = "hex::serde")]
+    pub tx_hash: Vec<u8>,
+    pub token: String,
+    /// Signed amount, in the smallest unit of the token.
+    pub amount: String,
+    /// `transfer`, `fee`, `bond`, `withdraw` or `ibc`.
+    pub kind: String,
+}
+
+impl TryFrom<&Row> for BalanceChangeInfo {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(Self {
+            block_id: row.try_get("block_id")?,
+            height: row.try_get("height")?,
+            tx_hash: row.try_get("tx_hash")?,
+            token: row.try_get("token")?,
+            amount: row.try_get("amount")?,
+            kind: row.try_get("kind")?,
+        })
+    }
+}
@@ -97,6 +97,8 @@ pub struct BlockInfo {
     pub header: Header,
     pub last_commit: Option<LastCommitInfo>,
     pub tx_hashes: Vec<TxShort>,
+    /// The Namada address of the proposer of the block, if known.
+    pub proposer: Option<String>,
 }
 
 impl From<BlockInfo> for Header {
@@ -242,6 +244,7 @@ impl TryFrom<&Row> for BlockInfo {
             header,
             last_commit,
             tx_hashes: vec![],
+            proposer: None,
         })
     }
 }
@@ -1,5 +1,11 @@
 pub mod account;
 pub mod address;
+pub mod balance;
 pub mod block;
+pub mod delegator;
+pub mod event;
+pub mod fee;
+pub mod governance;
+pub mod ibc;
 pub mod transaction;
 pub mod validator;
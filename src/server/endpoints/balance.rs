@@ -0,0 +1,67 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use serde::Deserialize;
+use tracing::info;
+
+use crate::{
+    server::{
+        balances::{BalanceChangeInfo, BalanceInfo},
+        ServerState,
+    },
+    Error,
+};
+
+// Default and max number of balance changes returned at once.
+const HISTORY_LIMIT: i64 = 100;
+const HISTORY_MAX_LIMIT: i64 = 1000;
+
+#[derive(Debug, Deserialize)]
+pub struct BalanceHistoryParams {
+    token: Option<String>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+pub async fn get_balances(
+    State(state): State<ServerState>,
+    Path(address): Path<String>,
+) -> Result<Json<Vec<BalanceInfo>>, Error> {
+    info!("calling /address/:address/balances");
+
+    let rows = state.db.get_balances(&address).await?;
+
+    let balances = rows
+        .iter()
+        .map(BalanceInfo::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Json(balances))
+}
+
+pub async fn get_balance_history(
+    State(state): State<ServerState>,
+    Path(address): Path<String>,
+    Query(params): Query<BalanceHistoryParams>,
+) -> Result<Json<Vec<BalanceChangeInfo>>, Error> {
+    info!("calling /address/:address/balances/history");
+
+    let limit = params
+        .limit
+        .unwrap_or(HISTORY_LIMIT)
+        .clamp(0, HISTORY_MAX_LIMIT);
+    let offset = params.offset.unwrap_or_default().max(0);
+
+    let rows = state
+        .db
+        .get_balance_history(&address, params.token.as_deref(), limit, offset)
+        .await?;
+
+    let changes = rows
+        .iter()
+        .map(BalanceChangeInfo::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Json(changes))
+}
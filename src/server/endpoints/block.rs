@@ -38,6 +38,13 @@ async fn get_tx_hashes(
     Ok(())
 }
 
+async fn get_proposer(state: &ServerState, block: &mut BlockInfo) -> Result<(), Error> {
+    let tm_address = block.header.proposer_address.to_string();
+    block.proposer = state.db.validator_by_tm_address(&tm_address).await?;
+
+    Ok(())
+}
+
 pub async fn get_block_by_hash(
     State(state): State<ServerState>,
     Path(hash): Path<String>,
@@ -54,6 +61,7 @@ pub async fn get_block_by_hash(
 
     let block_id: Vec<u8> = row.try_get("block_id")?;
     get_tx_hashes(&state, &mut block, &block_id).await?;
+    get_proposer(&state, &mut block).await?;
 
     Ok(Json(Some(block)))
 }
@@ -73,6 +81,7 @@ pub async fn get_block_by_height(
 
     let block_id: Vec<u8> = row.try_get("block_id")?;
     get_tx_hashes(&state, &mut block, &block_id).await?;
+    get_proposer(&state, &mut block).await?;
 
     Ok(Json(Some(block)))
 }
@@ -95,6 +104,7 @@ pub async fn get_last_block(
 
             let block_id: Vec<u8> = row.try_get("block_id")?;
             get_tx_hashes(&state, &mut block, &block_id).await?;
+            get_proposer(&state, &mut block).await?;
 
             blocks.push(block);
         }
@@ -107,6 +117,7 @@ pub async fn get_last_block(
 
         let block_id: Vec<u8> = row.try_get("block_id")?;
         get_tx_hashes(&state, &mut block, &block_id).await?;
+        get_proposer(&state, &mut block).await?;
 
         Ok(Json(LatestBlock::LastBlock(Box::new(block))))
     }
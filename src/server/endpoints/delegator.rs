@@ -0,0 +1,76 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use serde::Deserialize;
+use tracing::info;
+
+use crate::{
+    server::{
+        staking::{PositionInfo, UnbondInfo},
+        ServerState,
+    },
+    Error,
+};
+
+// Default and max number of unbonds returned at once.
+const UNBONDS_LIMIT: i64 = 100;
+const UNBONDS_MAX_LIMIT: i64 = 1000;
+
+#[derive(Debug, Deserialize)]
+pub struct PositionsParams {
+    height: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UnbondsParams {
+    pending: Option<bool>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+pub async fn get_delegator_positions(
+    State(state): State<ServerState>,
+    Path(address): Path<String>,
+    Query(params): Query<PositionsParams>,
+) -> Result<Json<Vec<PositionInfo>>, Error> {
+    info!("calling /delegator/:address/positions");
+
+    let rows = state
+        .db
+        .get_delegator_positions(&address, params.height)
+        .await?;
+
+    let positions = rows
+        .iter()
+        .map(PositionInfo::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Json(positions))
+}
+
+pub async fn get_delegator_unbonds(
+    State(state): State<ServerState>,
+    Path(address): Path<String>,
+    Query(params): Query<UnbondsParams>,
+) -> Result<Json<Vec<UnbondInfo>>, Error> {
+    info!("calling /delegator/:address/unbonds");
+
+    let limit = params
+        .limit
+        .unwrap_or(UNBONDS_LIMIT)
+        .clamp(0, UNBONDS_MAX_LIMIT);
+    let offset = params.offset.unwrap_or_default().max(0);
+
+    let rows = state
+        .db
+        .get_delegator_unbonds(&address, params.pending.unwrap_or_default(), limit, offset)
+        .await?;
+
+    let unbonds = rows
+        .iter()
+        .map(UnbondInfo::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Json(unbonds))
+}
@@ -0,0 +1,56 @@
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use serde::Deserialize;
+use tracing::info;
+
+use crate::{
+    server::{events::EventInfo, ServerState},
+    Error,
+};
+
+// Default and max number of events returned at once.
+const EVENTS_LIMIT: i64 = 100;
+const EVENTS_MAX_LIMIT: i64 = 1000;
+
+#[derive(Debug, Deserialize)]
+pub struct EventsParams {
+    #[serde(rename = "type")]
+    event_type: Option<String>,
+    key: Option<String>,
+    value: Option<String>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+pub async fn get_events(
+    State(state): State<ServerState>,
+    Query(params): Query<EventsParams>,
+) -> Result<Json<Vec<EventInfo>>, Error> {
+    info!("calling /events");
+
+    let limit = params
+        .limit
+        .unwrap_or(EVENTS_LIMIT)
+        .clamp(0, EVENTS_MAX_LIMIT);
+    let offset = params.offset.unwrap_or_default().max(0);
+
+    let rows = state
+        .db
+        .get_events(
+            params.event_type.as_deref(),
+            params.key.as_deref(),
+            params.value.as_deref(),
+            limit,
+            offset,
+        )
+        .await?;
+
+    let events = rows
+        .iter()
+        .map(EventInfo::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Json(events))
+}
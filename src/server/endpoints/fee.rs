@@ -0,0 +1,43 @@
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use serde::Deserialize;
+use sqlx::Row as TRow;
+use tracing::info;
+
+use crate::{
+    server::{fees::FeeInfo, ServerState},
+    Error,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct FeesParams {
+    start: Option<u32>,
+    end: Option<u32>,
+}
+
+pub async fn get_fees(
+    State(state): State<ServerState>,
+    Query(params): Query<FeesParams>,
+) -> Result<Json<Vec<FeeInfo>>, Error> {
+    info!("calling /fees");
+
+    let end = match params.end {
+        Some(end) => end,
+        None => {
+            let last: Option<i32> = state.db.get_last_height().await?.try_get("header_height")?;
+            last.unwrap_or_default() as u32
+        }
+    };
+    let start = params.start.unwrap_or(1);
+
+    let rows = state.db.get_fees(start, end).await?;
+
+    let fees = rows
+        .iter()
+        .map(FeeInfo::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Json(fees))
+}
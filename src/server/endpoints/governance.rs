@@ -0,0 +1,72 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use serde::Deserialize;
+use tracing::info;
+
+use crate::{
+    server::{
+        governance::{ProposalInfo, TallyInfo},
+        ServerState,
+    },
+    Error,
+};
+
+// Default and max number of proposals returned at once.
+const PROPOSALS_LIMIT: i64 = 100;
+const PROPOSALS_MAX_LIMIT: i64 = 1000;
+
+#[derive(Debug, Deserialize)]
+pub struct ProposalsParams {
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+pub async fn get_proposals(
+    State(state): State<ServerState>,
+    Query(params): Query<ProposalsParams>,
+) -> Result<Json<Vec<ProposalInfo>>, Error> {
+    info!("calling /proposals");
+
+    let limit = params
+        .limit
+        .unwrap_or(PROPOSALS_LIMIT)
+        .clamp(0, PROPOSALS_MAX_LIMIT);
+    let offset = params.offset.unwrap_or_default().max(0);
+
+    let rows = state.db.get_proposals(limit, offset).await?;
+
+    let proposals = rows
+        .iter()
+        .map(ProposalInfo::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Json(proposals))
+}
+
+pub async fn get_proposal(
+    State(state): State<ServerState>,
+    Path(proposal_id): Path<i64>,
+) -> Result<Json<Option<ProposalInfo>>, Error> {
+    info!("calling /proposal/:proposal_id");
+
+    let row = state.db.get_proposal(proposal_id).await?;
+    let Some(row) = row else {
+        return Ok(Json(None));
+    };
+
+    Ok(Json(Some(ProposalInfo::try_from(&row)?)))
+}
+
+pub async fn get_proposal_tally(
+    State(state): State<ServerState>,
+    Path(proposal_id): Path<i64>,
+) -> Result<Json<TallyInfo>, Error> {
+    info!("calling /proposal/:proposal_id/tally");
+
+    let rows = state.db.get_proposal_tally(proposal_id).await?;
+    let tally = TallyInfo::from_rows(proposal_id, &rows)?;
+
+    Ok(Json(tally))
+}
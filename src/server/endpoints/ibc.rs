@@ -0,0 +1,55 @@
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use serde::Deserialize;
+use tracing::info;
+
+use crate::{
+    server::{ibc::IbcTransferInfo, ServerState},
+    Error,
+};
+
+// Default and max number of transfers returned at once.
+const TRANSFERS_LIMIT: i64 = 100;
+const TRANSFERS_MAX_LIMIT: i64 = 1000;
+
+#[derive(Debug, Deserialize)]
+pub struct IbcTransfersParams {
+    address: Option<String>,
+    channel: Option<String>,
+    denom: Option<String>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+pub async fn get_ibc_transfers(
+    State(state): State<ServerState>,
+    Query(params): Query<IbcTransfersParams>,
+) -> Result<Json<Vec<IbcTransferInfo>>, Error> {
+    info!("calling /ibc/transfers");
+
+    let limit = params
+        .limit
+        .unwrap_or(TRANSFERS_LIMIT)
+        .clamp(0, TRANSFERS_MAX_LIMIT);
+    let offset = params.offset.unwrap_or_default().max(0);
+
+    let rows = state
+        .db
+        .get_ibc_transfers(
+            params.address.as_deref(),
+            params.channel.as_deref(),
+            params.denom.as_deref(),
+            limit,
+            offset,
+        )
+        .await?;
+
+    let transfers = rows
+        .iter()
+        .map(IbcTransferInfo::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Json(transfers))
+}
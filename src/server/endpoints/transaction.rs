@@ -5,12 +5,10 @@ use axum::{
 use tracing::info;
 
 use crate::{
-    server::{shielded, ServerState, TxInfo},
+    server::{shielded, tx::VoteProposalTx, ServerState, TxInfo},
     Error,
 };
 
-use sqlx::Row as TRow;
-
 pub async fn get_tx_by_hash(
     State(state): State<ServerState>,
     Path(hash): Path<String>,
@@ -42,14 +40,15 @@ pub async fn get_shielded_tx(
 pub async fn get_vote_proposal(
     State(state): State<ServerState>,
     Path(proposal_id): Path<i64>,
-) -> Result<Json<serde_json::Value>, Error> {
-    let mut votes: Vec<serde_json::Value> = vec![];
+) -> Result<Json<Vec<VoteProposalTx>>, Error> {
+    info!("calling /tx/vote_proposal/:proposal_id");
+
     let rows = state.db.vote_proposal_data(proposal_id).await?;
-    for row in rows {
-        let vote_proposal_data: serde_json::Value = row.try_get("data")?;
 
-        votes.push(vote_proposal_data);
-    }
+    let votes = rows
+        .iter()
+        .map(VoteProposalTx::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
 
-    Ok(Json(serde_json::Value::Array(votes)))
+    Ok(Json(votes))
 }
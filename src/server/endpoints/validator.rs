@@ -8,7 +8,97 @@ use sqlx::Row as TRow;
 use std::collections::HashMap;
 use tracing::{info, instrument};
 
-use crate::{server::ServerState, Error};
+use crate::{
+    server::{
+        staking::DelegatorInfo,
+        validators::{
+            CommissionChange, ConsensusKeyChange, MetadataChange, ProposedBlock, ProposerInfo,
+            ProposersInfo, SignaturesInfo, StateChange, TmAddressRange, UptimeInfo,
+            ValidatorDetail, ValidatorInfo, ValidatorsUptime,
+        },
+        ServerState,
+    },
+    Error,
+};
+
+// Default and max number of delegators returned at once.
+const DELEGATORS_LIMIT: i64 = 100;
+const DELEGATORS_MAX_LIMIT: i64 = 1000;
+
+// Default and max number of validators returned at once.
+const VALIDATORS_LIMIT: i64 = 100;
+const VALIDATORS_MAX_LIMIT: i64 = 1000;
+
+// Default and max number of proposed blocks returned at once.
+const PROPOSED_LIMIT: i64 = 100;
+const PROPOSED_MAX_LIMIT: i64 = 1000;
+
+// Default and max number of blocks the uptime is computed over.
+const UPTIME_WINDOW: i32 = 500;
+const UPTIME_MAX_WINDOW: i32 = 10000;
+
+// Default and max number of heights the signatures are returned for.
+const SIGNATURES_WINDOW: i32 = 500;
+const SIGNATURES_MAX_WINDOW: i32 = 10000;
+
+// Prefix of the Namada addresses, validators can also be given by
+// the hex encoded Tendermint address of one of their consensus keys.
+const NAMADA_ADDRESS_PREFIX: &str = "tnam";
+
+/// Returns the Tendermint addresses of the consensus keys of a validator
+/// given by its Namada address or one of its Tendermint addresses.
+async fn tm_addresses(state: &ServerState, address: &str) -> Result<Vec<Vec<u8>>, Error> {
+    if !address.starts_with(NAMADA_ADDRESS_PREFIX) {
+        return Ok(vec![hex::decode(address)?]);
+    }
+
+    let rows = state.db.get_validator_tm_addresses(address).await?;
+
+    rows.iter()
+        .map(|row| -> Result<Vec<u8>, Error> {
+            let tm_address: String = row.try_get("tm_address")?;
+            Ok(hex::decode(tm_address)?)
+        })
+        .collect()
+}
+
+/// Returns the first and last heights of the blocks the uptime is computed
+/// over: the blocks after `start` up to `end` if both are set, the last
+/// `window` blocks indexed otherwise.
+async fn uptime_range(
+    state: &ServerState,
+    start: Option<i32>,
+    end: Option<i32>,
+    window: Option<i32>,
+) -> Result<(i32, i32), Error> {
+    if let (Some(start), Some(end)) = (start, end) {
+        return Ok((start + 1, end));
+    }
+
+    let window = window.unwrap_or(UPTIME_WINDOW).clamp(1, UPTIME_MAX_WINDOW);
+
+    let row = state.db.get_last_height().await?;
+    let last: Option<i32> = row.try_get("header_height")?;
+    let last = last.unwrap_or_default();
+
+    Ok(((last - window + 1).max(1), last))
+}
+
+/// Returns the Namada address of a validator given by its Namada address
+/// or one of its Tendermint addresses, unknown Tendermint addresses are
+/// returned as is.
+async fn namada_address(state: &ServerState, address: &str) -> Result<String, Error> {
+    if address.starts_with(NAMADA_ADDRESS_PREFIX) {
+        return Ok(address.to_string());
+    }
+
+    let validator = state
+        .db
+        .validator_by_tm_address(&address.to_uppercase())
+        .await?;
+
+    Ok(validator.unwrap_or_else(|| address.to_string()))
+}
 
 // Retrieve the count of commit for a range of blocks from the sql query result.
 #[derive(Debug, Serialize, Deserialize, PartialEq, Default)]
@@ -31,6 +121,44 @@ pub struct UptimeValue {
     pub uptime: f64,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ValidatorsParams {
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UptimeParams {
+    start: Option<i32>,
+    end: Option<i32>,
+    window: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProposedParams {
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProposersParams {
+    from: Option<i32>,
+    to: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SignaturesParams {
+    window: Option<i32>,
+    min_streak: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DelegatorsParams {
+    height: Option<i32>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
 pub async fn get_validator_uptime(
     State(state): State<ServerState>,
     Path(validator_address): Path<String>,
@@ -38,23 +166,249 @@ pub async fn get_validator_uptime(
 ) -> Result<Json<UptimeValue>, Error> {
     info!("calling /validator/:validator_address/uptime");
 
-    let start = params.get("start");
-    let end = params.get("end");
+    let start = params.get("start").copied();
+    let end = params.get("end").copied();
+    let window = params.get("window").copied();
+    let (start, end) = uptime_range(&state, start, end, window).await?;
 
-    let va = hex::decode(validator_address)?;
+    let va = tm_addresses(&state, &validator_address).await?;
     let row = state.db.validator_uptime(&va, start, end).await?;
     let cc = CommitCount::try_from(&row)?;
 
-    // default range is 500 blocks
-    let mut ranger_size: f64 = 500.0;
-
-    if let (Some(s), Some(e)) = (start, end) {
-        ranger_size = (e - s).into();
-    }
+    // only the blocks indexed in the range are expected to be signed
+    let blocks = state.db.count_blocks(start, end).await?;
 
     let uv = UptimeValue {
-        uptime: (cc.0 as f64) / ranger_size,
+        uptime: if blocks > 0 {
+            (cc.0 as f64) / (blocks as f64)
+        } else {
+            0.0
+        },
     };
 
     Ok(Json(uv))
 }
+
+pub async fn get_validator_delegators(
+    State(state): State<ServerState>,
+    Path(validator_address): Path<String>,
+    Query(params): Query<DelegatorsParams>,
+) -> Result<Json<Vec<DelegatorInfo>>, Error> {
+    info!("calling /validator/:validator_address/delegators");
+
+    let limit = params
+        .limit
+        .unwrap_or(DELEGATORS_LIMIT)
+        .clamp(0, DELEGATORS_MAX_LIMIT);
+    let offset = params.offset.unwrap_or_default().max(0);
+
+    let validator_address = namada_address(&state, &validator_address).await?;
+    let rows = state
+        .db
+        .get_validator_delegators(&validator_address, params.height, limit, offset)
+        .await?;
+
+    let delegators = rows
+        .iter()
+        .map(DelegatorInfo::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Json(delegators))
+}
+
+pub async fn get_validators(
+    State(state): State<ServerState>,
+    Query(params): Query<ValidatorsParams>,
+) -> Result<Json<Vec<ValidatorInfo>>, Error> {
+    info!("calling /validators");
+
+    let limit = params
+        .limit
+        .unwrap_or(VALIDATORS_LIMIT)
+        .clamp(0, VALIDATORS_MAX_LIMIT);
+    let offset = params.offset.unwrap_or_default().max(0);
+
+    let rows = state.db.get_validators(limit, offset).await?;
+
+    let validators = rows
+        .iter()
+        .map(ValidatorInfo::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Json(validators))
+}
+
+pub async fn get_validator(
+    State(state): State<ServerState>,
+    Path(validator_address): Path<String>,
+) -> Result<Json<Option<ValidatorDetail>>, Error> {
+    info!("calling /validator/:validator_address");
+
+    let validator_address = namada_address(&state, &validator_address).await?;
+    let row = state.db.get_validator(&validator_address).await?;
+    let Some(row) = row else {
+        return Ok(Json(None));
+    };
+
+    let states = state
+        .db
+        .get_validator_states(&validator_address)
+        .await?
+        .iter()
+        .map(StateChange::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+    let commissions = state
+        .db
+        .get_validator_commissions(&validator_address)
+        .await?
+        .iter()
+        .map(CommissionChange::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+    let metadata = state
+        .db
+        .get_validator_metadata(&validator_address)
+        .await?
+        .iter()
+        .map(MetadataChange::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+    let consensus_keys = state
+        .db
+        .get_validator_consensus_keys(&validator_address)
+        .await?
+        .iter()
+        .map(ConsensusKeyChange::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+    let tm_addresses = state
+        .db
+        .get_validator_tm_addresses(&validator_address)
+        .await?
+        .iter()
+        .map(TmAddressRange::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Json(Some(ValidatorDetail {
+        validator: ValidatorInfo::try_from(&row)?,
+        states,
+        commissions,
+        metadata,
+        consensus_keys,
+        tm_addresses,
+    })))
+}
+
+pub async fn get_validator_signatures(
+    State(state): State<ServerState>,
+    Path(validator_address): Path<String>,
+    Query(params): Query<SignaturesParams>,
+) -> Result<Json<SignaturesInfo>, Error> {
+    info!("calling /validator/:validator_address/signatures");
+
+    let window = params
+        .window
+        .unwrap_or(SIGNATURES_WINDOW)
+        .clamp(1, SIGNATURES_MAX_WINDOW);
+
+    // the commit of the last block is only saved with the next one
+    let row = state.db.get_last_height().await?;
+    let last: Option<i32> = row.try_get("header_height")?;
+    let end = last.unwrap_or_default() - 1;
+    let start = (end - window + 1).max(1);
+
+    let va = tm_addresses(&state, &validator_address).await?;
+    let rows = state.db.get_validator_signatures(&va, start, end).await?;
+
+    let flags = rows
+        .iter()
+        .map(|row| -> Result<(i32, Option<i32>), Error> {
+            Ok((row.try_get("height")?, row.try_get("block_id_flag")?))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let min_streak = params.min_streak.unwrap_or(1);
+
+    Ok(Json(SignaturesInfo::new(start, end, &flags, min_streak)))
+}
+
+pub async fn get_validators_uptime(
+    State(state): State<ServerState>,
+    Query(params): Query<UptimeParams>,
+) -> Result<Json<ValidatorsUptime>, Error> {
+    info!("calling /validators/uptime");
+
+    let (start, end) = uptime_range(&state, params.start, params.end, params.window).await?;
+
+    let blocks = state.db.count_blocks(start, end).await?;
+    let rows = state.db.validators_uptime(start, end).await?;
+
+    let validators = rows
+        .iter()
+        .map(|row| UptimeInfo::from_row(row, blocks))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Json(ValidatorsUptime {
+        start_height: start,
+        end_height: end,
+        blocks,
+        validators,
+    }))
+}
+
+pub async fn get_validator_proposed(
+    State(state): State<ServerState>,
+    Path(validator_address): Path<String>,
+    Query(params): Query<ProposedParams>,
+) -> Result<Json<Vec<ProposedBlock>>, Error> {
+    info!("calling /validator/:validator_address/proposed");
+
+    let limit = params
+        .limit
+        .unwrap_or(PROPOSED_LIMIT)
+        .clamp(0, PROPOSED_MAX_LIMIT);
+    let offset = params.offset.unwrap_or_default().max(0);
+
+    // proposer addresses are stored upper case
+    let va = tm_addresses(&state, &validator_address)
+        .await?
+        .iter()
+        .map(hex::encode_upper)
+        .collect::<Vec<_>>();
+    let rows = state.db.get_proposed_blocks(&va, limit, offset).await?;
+
+    let blocks = rows
+        .iter()
+        .map(ProposedBlock::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Json(blocks))
+}
+
+pub async fn get_validators_proposers(
+    State(state): State<ServerState>,
+    Query(params): Query<ProposersParams>,
+) -> Result<Json<ProposersInfo>, Error> {
+    info!("calling /validators/proposers");
+
+    let end = match params.to {
+        Some(to) => to,
+        None => {
+            let row = state.db.get_last_height().await?;
+            let last: Option<i32> = row.try_get("header_height")?;
+            last.unwrap_or_default()
+        }
+    };
+    let start = params.from.unwrap_or(1);
+
+    let blocks = state.db.count_blocks(start, end).await?;
+    let rows = state.db.get_proposers(start, end).await?;
+
+    let proposers = rows
+        .iter()
+        .map(|row| ProposerInfo::from_row(row, blocks))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Json(ProposersInfo {
+        start_height: start,
+        end_height: end,
+        blocks,
+        proposers,
+    }))
+}
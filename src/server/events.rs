@@ -0,0 +1,44 @@
+use crate::error::Error;
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgRow as Row;
+use sqlx::Row as TRow;
+
+use super::utils::serialize_optional_hex;
+
+/// An event emitted by the node while processing a block.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct EventInfo {
+    /// The block this event belongs to.
+    #[serde(with = "hex::serde")]
+    pub block_id: Vec<u8>,
+    pub height: i32,
+    /// Where the event comes from: begin_block, tx_result,
+    /// end_block or finalize_block.
+    pub source: String,
+    /// Position of the event in its source.
+    pub event_index: i32,
+    /// Position of the transaction in the block for tx_result events.
+    pub tx_index: Option<i32>,
+    /// The transaction this event is about, if any.
+    #[serde(serialize_with = "serialize_optional_hex")]
+    pub tx_hash: Option<Vec<u8>>,
+    pub event_type: String,
+    pub attributes: serde_json::Value,
+}
+
+impl TryFrom<&Row> for EventInfo {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(Self {
+            block_id: row.try_get("block_id")?,
+            height: row.try_get("height")?,
+            source: row.try_get("source")?,
+            event_index: row.try_get("event_index")?,
+            tx_index: row.try_get("tx_index")?,
+            tx_hash: row.try_get("tx_hash")?,
+            event_type: row.try_get("event_type")?,
+            attributes: row.try_get("attributes")?,
+        })
+    }
+}
@@ -0,0 +1,30 @@
+use crate::error::Error;
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgRow as Row;
+use sqlx::Row as TRow;
+
+/// Fees paid in a token over a range of heights.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct FeeInfo {
+    pub fee_token: Option<String>,
+    /// Sum of the fee paid by the wrappers, empty if none of their
+    /// inner transactions has been executed yet.
+    pub fee_paid: Option<String>,
+    /// Sum of the gas used by the inner transactions.
+    pub gas_used: Option<i64>,
+    /// Number of wrappers paying fees in this token.
+    pub tx_count: i64,
+}
+
+impl TryFrom<&Row> for FeeInfo {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(Self {
+            fee_token: row.try_get("fee_token")?,
+            fee_paid: row.try_get("fee_paid")?,
+            gas_used: row.try_get("gas_used")?,
+            tx_count: row.try_get("tx_count")?,
+        })
+    }
+}
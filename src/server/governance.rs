@@ -0,0 +1,80 @@
+use crate::error::Error;
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgRow as Row;
+use sqlx::Row as TRow;
+
+/// A governance proposal.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ProposalInfo {
+    pub id: i64,
+    /// The block the proposal has been made in.
+    #[serde(with = "hex::serde")]
+    pub block_id: Vec<u8>,
+    pub height: i32,
+    #[serde(with = "hex::serde")]
+    pub tx_hash: Vec<u8>,
+    pub author: String,
+    /// `Default`, `PGFSteward` or `PGFPayment`.
+    pub proposal_type: String,
+    /// The proposal type with its data, as decoded.
+    pub type_data: Option<serde_json::Value>,
+    pub voting_start_epoch: i64,
+    pub voting_end_epoch: i64,
+    pub grace_epoch: i64,
+    pub content: Option<serde_json::Value>,
+}
+
+impl TryFrom<&Row> for ProposalInfo {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: row.try_get("proposal_id")?,
+            block_id: row.try_get("block_id")?,
+            height: row.try_get("height")?,
+            tx_hash: row.try_get("tx_hash")?,
+            author: row.try_get("author")?,
+            proposal_type: row.try_get("proposal_type")?,
+            type_data: row.try_get("type_data")?,
+            voting_start_epoch: row.try_get("voting_start_epoch")?,
+            voting_end_epoch: row.try_get("voting_end_epoch")?,
+            grace_epoch: row.try_get("grace_epoch")?,
+            content: row.try_get("content")?,
+        })
+    }
+}
+
+/// The number of votes by option on a proposal, counting only the last
+/// vote of each voter.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Default)]
+pub struct TallyInfo {
+    pub id: i64,
+    pub yay: i64,
+    pub nay: i64,
+    pub abstain: i64,
+    pub total: i64,
+}
+
+impl TallyInfo {
+    pub fn from_rows(id: i64, rows: &[Row]) -> Result<Self, Error> {
+        let mut tally = Self {
+            id,
+            ..Default::default()
+        };
+
+        for row in rows {
+            let vote: String = row.try_get("vote")?;
+            let count: i64 = row.try_get("count")?;
+
+            match vote.as_str() {
+                "yay" => tally.yay += count,
+                "nay" => tally.nay += count,
+                "abstain" => tally.abstain += count,
+                _ => {}
+            }
+            tally.total += count;
+        }
+
+        Ok(tally)
+    }
+}
@@ -0,0 +1,46 @@
+use crate::error::Error;
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgRow as Row;
+use sqlx::Row as TRow;
+
+/// A fungible token transfer sent or received through IBC.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct IbcTransferInfo {
+    /// The block the transaction belongs to.
+    #[serde(with = "hex::serde")]
+    pub block_id: Vec<u8>,
+    pub height: i32,
+    #[serde(with = "hex::serde")]
+    pub tx_hash: Vec<u8>,
+    /// `send` for outgoing transfers, `receive` for incoming ones.
+    pub direction: String,
+    pub sender: String,
+    pub receiver: String,
+    pub denom: String,
+    pub amount: String,
+    /// Port and channel on the Namada side.
+    pub port: String,
+    pub channel: String,
+    /// Packet sequence, only known for received transfers.
+    pub sequence: Option<i64>,
+}
+
+impl TryFrom<&Row> for IbcTransferInfo {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(Self {
+            block_id: row.try_get("block_id")?,
+            height: row.try_get("height")?,
+            tx_hash: row.try_get("tx_hash")?,
+            direction: row.try_get("direction")?,
+            sender: row.try_get("sender")?,
+            receiver: row.try_get("receiver")?,
+            denom: row.try_get("denom")?,
+            amount: row.try_get("amount")?,
+            port: row.try_get("port")?,
+            channel: row.try_get("channel")?,
+            sequence: row.try_get("sequence")?,
+        })
+    }
+}
@@ -14,22 +14,38 @@ use crate::config::ServerConfig;
 use crate::database::Database;
 use crate::error::Error;
 
+pub mod balances;
 pub mod blocks;
+pub mod events;
+pub mod fees;
+pub mod governance;
+pub mod ibc;
 pub mod tx;
+pub mod validators;
 pub use blocks::BlockInfo;
 pub use tx::TxInfo;
 pub mod account;
 mod endpoints;
 pub mod shielded;
+pub mod staking;
 mod utils;
 pub(crate) use utils::{from_hex, serialize_hex};
 
 use self::endpoints::{
     account::get_account_updates,
     address::get_txs_by_address,
+    balance::{get_balance_history, get_balances},
     block::{get_block_by_hash, get_block_by_height, get_last_block},
+    delegator::{get_delegator_positions, get_delegator_unbonds},
+    event::get_events,
+    fee::get_fees,
+    governance::{get_proposal, get_proposal_tally, get_proposals},
+    ibc::get_ibc_transfers,
     transaction::{get_shielded_tx, get_tx_by_hash, get_vote_proposal},
-    validator::get_validator_uptime,
+    validator::{
+        get_validator, get_validator_delegators, get_validator_proposed, get_validator_signatures,
+        get_validator_uptime, get_validators, get_validators_proposers, get_validators_uptime,
+    },
 };
 
 pub const HTTP_DURATION_SECONDS_BUCKETS: &[f64; 11] = &[
@@ -47,6 +63,11 @@ fn server_routes(state: ServerState) -> Router<()> {
         .allow_origin(Any);
     Router::new()
         .route("/address/:address", get(get_txs_by_address))
+        .route("/address/:address/balances", get(get_balances))
+        .route(
+            "/address/:address/balances/history",
+            get(get_balance_history),
+        )
         .route("/block/height/:block_height", get(get_block_by_height))
         .route("/block/hash/:block_hash", get(get_block_by_hash))
         .route("/block/last", get(get_last_block))
@@ -54,10 +75,37 @@ fn server_routes(state: ServerState) -> Router<()> {
         .route("/tx/vote_proposal/:proposal_id", get(get_vote_proposal))
         .route("/tx/shielded", get(get_shielded_tx))
         .route("/account/updates/:account_id", get(get_account_updates))
+        .route("/events", get(get_events))
+        .route("/fees", get(get_fees))
+        .route("/ibc/transfers", get(get_ibc_transfers))
+        .route("/proposals", get(get_proposals))
+        .route("/proposal/:proposal_id", get(get_proposal))
+        .route("/proposal/:proposal_id/tally", get(get_proposal_tally))
+        .route(
+            "/delegator/:address/positions",
+            get(get_delegator_positions),
+        )
+        .route("/delegator/:address/unbonds", get(get_delegator_unbonds))
+        .route("/validators", get(get_validators))
+        .route("/validators/uptime", get(get_validators_uptime))
+        .route("/validators/proposers", get(get_validators_proposers))
+        .route("/validator/:validator_address", get(get_validator))
         .route(
             "/validator/:validator_address/uptime",
             get(get_validator_uptime),
         )
+        .route(
+            "/validator/:validator_address/delegators",
+            get(get_validator_delegators),
+        )
+        .route(
+            "/validator/:validator_address/signatures",
+            get(get_validator_signatures),
+        )
+        .route(
+            "/validator/:validator_address/proposed",
+            get(get_validator_proposed),
+        )
         .layer(cors)
         .with_state(state)
 }
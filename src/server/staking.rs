@@ -0,0 +1,83 @@
+use crate::error::Error;
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgRow as Row;
+use sqlx::Row as TRow;
+
+/// The tokens of a delegator with one validator, in NAM.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct PositionInfo {
+    pub validator: String,
+    pub bonded: String,
+    /// Unbonded but not withdrawn yet.
+    pub unbonding: String,
+    pub withdrawn: String,
+}
+
+impl TryFrom<&Row> for PositionInfo {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(Self {
+            validator: row.try_get("validator")?,
+            bonded: row.try_get("bonded")?,
+            unbonding: row.try_get("unbonding")?,
+            withdrawn: row.try_get("withdrawn")?,
+        })
+    }
+}
+
+/// An unbond of a delegator and the withdrawal it has been withdrawn by.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct UnbondInfo {
+    #[serde(with = "hex::serde")]
+    pub block_id: Vec<u8>,
+    pub height: i32,
+    pub epoch: Option<i64>,
+    #[serde(with = "hex::serde")]
+    pub tx_hash: Vec<u8>,
+    pub validator: String,
+    pub amount: String,
+    /// The epoch the tokens can be withdrawn at, unknown without the epoch
+    /// of the unbond.
+    pub withdrawable_epoch: Option<i64>,
+    pub withdrawal_tx_hash: Option<String>,
+    pub withdrawal_height: Option<i32>,
+}
+
+impl TryFrom<&Row> for UnbondInfo {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        let withdrawal_tx_hash: Option<Vec<u8>> = row.try_get("withdrawal_tx_hash")?;
+
+        Ok(Self {
+            block_id: row.try_get("block_id")?,
+            height: row.try_get("height")?,
+            epoch: row.try_get("epoch")?,
+            tx_hash: row.try_get("tx_hash")?,
+            validator: row.try_get("validator")?,
+            amount: row.try_get("amount")?,
+            withdrawable_epoch: row.try_get("withdrawable_epoch")?,
+            withdrawal_tx_hash: withdrawal_tx_hash.map(hex::encode),
+            withdrawal_height: row.try_get("withdrawal_height")?,
+        })
+    }
+}
+
+/// The tokens bonded by a delegator to a validator, in NAM.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct DelegatorInfo {
+    pub delegator: String,
+    pub bonded: String,
+}
+
+impl TryFrom<&Row> for DelegatorInfo {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(Self {
+            delegator: row.try_get("delegator")?,
+            bonded: row.try_get("bonded")?,
+        })
+    }
+}
@@ -1,5 +1,4 @@
 use crate::error::Error;
-use namada_sdk::ibc::primitives::proto::Any;
 use serde::{Deserialize, Serialize};
 
 use super::utils::serialize_optional_hex;
@@ -7,22 +6,6 @@ use super::utils::serialize_optional_hex;
 use sqlx::postgres::PgRow as Row;
 use sqlx::Row as TRow;
 
-// namada::ibc::applications::transfer::msgs::transfer::TYPE_URL has been made private and can't be access anymore
-// const MSG_TRANSFER_TYPE_URL: &str = "/ibc.applications.transfer.v1.MsgTransfer";
-
-// we have a variant for MsgTransfer, but there are other message types
-// defined in https://github.com/cosmos/ibc-rs/blob/main/crates/ibc/src/core/msgs.rs
-// however none of them implement serde traits, so lets use Any as the general
-// abstraction for it. Any is serializeable
-/// Defines the support IBC transactions.
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
-pub enum IbcTx {
-    // we do not use MsgTransfer directly as it does not
-    // implements serde traits.
-    MsgTransfer(Any),
-    Any(Any),
-}
-
 /// The relevant information regarding transactions and their types.
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 pub struct TxInfo {
@@ -34,9 +17,12 @@ pub struct TxInfo {
     block_id: Vec<u8>,
     /// The transaction type encoded as a string
     tx_type: String,
-    /// id for the wrapper tx if the tx is decrypted. otherwise it is null.
-    #[serde(with = "hex::serde")]
-    wrapper_id: Vec<u8>,
+    /// id for the wrapper tx if the tx is decrypted. otherwise it is empty.
+    #[serde(serialize_with = "serialize_optional_hex")]
+    wrapper_id: Option<Vec<u8>>,
+    /// id of the decrypted tx if the tx is a wrapper. otherwise it is empty.
+    #[serde(serialize_with = "serialize_optional_hex")]
+    inner_hash: Option<Vec<u8>>,
     /// The transaction fee only for tx_type Wrapper (otherwise empty)
     fee_amount_per_gas_unit: Option<String>,
     fee_token: Option<String>,
@@ -47,6 +33,14 @@ pub struct TxInfo {
     code: Option<Vec<u8>>,
     data: Option<serde_json::Value>,
     return_code: Option<i32>, // New field for return_code
+    /// Gas used by the execution of the transaction (only for Decrypted tx)
+    gas_used: Option<i64>,
+    /// Info and log returned by the execution of the transaction
+    info: Option<String>,
+    log: Option<String>,
+    /// The fee paid, the fee amount per gas unit times the gas used.
+    /// Set on both the wrapper and its decrypted tx once it has been executed.
+    fee_paid: Option<String>,
 }
 
 impl TxInfo {
@@ -65,17 +59,6 @@ impl TxInfo {
     pub fn data(&self) -> serde_json::Value {
         self.data.clone().unwrap_or_default()
     }
-
-    // fn decode_ibc(tx_data: &[u8]) -> Result<IbcTx, Error> {
-    //     let msg = Any::decode(tx_data).map_err(|e| Error::InvalidTxData(e.to_string()))?;
-    //     if msg.type_url.as_str() == MSG_TRANSFER_TYPE_URL
-    //         && MsgTransfer::try_from(msg.clone()).is_ok()
-    //     {
-    //         Ok(IbcTx::MsgTransfer(msg))
-    //     } else {
-    //         Ok(IbcTx::Any(msg))
-    //     }
-    // }
 }
 
 impl TryFrom<Row> for TxInfo {
@@ -85,58 +68,63 @@ impl TryFrom<Row> for TxInfo {
         let hash: Vec<u8> = row.try_get("hash")?;
         let block_id: Vec<u8> = row.try_get("block_id")?;
         let tx_type: String = row.try_get("tx_type")?;
-        let wrapper_id: Vec<u8> = row.try_get("wrapper_id")?;
+        let wrapper_id: Option<Vec<u8>> = row.try_get("wrapper_id")?;
+        let inner_hash: Option<Vec<u8>> = row.try_get("inner_hash")?;
         let fee_amount_per_gas_unit = row.try_get("fee_amount_per_gas_unit")?;
         let fee_token = row.try_get("fee_token")?;
         let gas_limit_multiplier = row.try_get("gas_limit_multiplier")?;
         let code: Option<Vec<u8>> = row.try_get("code")?;
         let data: Option<serde_json::Value> = row.try_get("data")?;
         let return_code = row.try_get("return_code")?;
+        let gas_used = row.try_get("gas_used")?;
+        let info = row.try_get("info")?;
+        let log = row.try_get("log")?;
+        let fee_paid = row.try_get("fee_paid")?;
 
         Ok(Self {
             hash,
             block_id,
             tx_type,
             wrapper_id,
+            inner_hash,
             fee_amount_per_gas_unit,
             fee_token,
             gas_limit_multiplier,
             code,
             data,
             return_code, // Assigning return_code to the struct field
+            gas_used,
+            info,
+            log,
+            fee_paid,
         })
     }
 }
 
+/// A vote cast on a governance proposal.
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 pub struct VoteProposalTx {
     pub id: i64,
+    /// `yay`, `nay` or `abstain`.
     pub vote: String,
     pub voter: String,
-    pub delegations: Vec<String>,
+    pub height: i32,
+    pub epoch: Option<i64>,
     #[serde(with = "hex::serde")]
     pub tx_id: Vec<u8>,
 }
 
-impl TryFrom<Row> for VoteProposalTx {
+impl TryFrom<&Row> for VoteProposalTx {
     type Error = Error;
 
-    fn try_from(value: Row) -> Result<Self, Self::Error> {
-        let id = value.try_get::<i64, _>("vote_proposal_id")?;
-
-        let vote = value.try_get::<String, _>("vote")?;
-        let voter = value.try_get::<String, _>("voter")?;
-        let tx_id = value.try_get::<Vec<u8>, _>("tx_id")?;
-
-        // empty this comes from another table.
-        let delegations = vec![];
-
+    fn try_from(value: &Row) -> Result<Self, Self::Error> {
         Ok(Self {
-            id,
-            vote,
-            voter,
-            delegations,
-            tx_id,
+            id: value.try_get("proposal_id")?,
+            vote: value.try_get("vote")?,
+            voter: value.try_get("voter")?,
+            height: value.try_get("height")?,
+            epoch: value.try_get("epoch")?,
+            tx_id: value.try_get("tx_hash")?,
         })
     }
 }
@@ -0,0 +1,465 @@
+use crate::database::{BLOCK_ID_FLAG_COMMIT, BLOCK_ID_FLAG_NIL};
+use crate::error::Error;
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgRow as Row;
+use sqlx::Row as TRow;
+
+/// The current state of a validator.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ValidatorInfo {
+    pub address: String,
+    pub registered_height: i32,
+    /// `active` or `inactive` once deactivated.
+    pub state: Option<String>,
+    pub consensus_key: Option<String>,
+    pub commission_rate: Option<String>,
+    pub max_commission_rate_change: Option<String>,
+    pub email: Option<String>,
+    pub description: Option<String>,
+    pub website: Option<String>,
+    pub discord_handle: Option<String>,
+    pub avatar: Option<String>,
+}
+
+impl TryFrom<&Row> for ValidatorInfo {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(Self {
+            address: row.try_get("address")?,
+            registered_height: row.try_get("registered_height")?,
+            state: row.try_get("state")?,
+            consensus_key: row.try_get("consensus_key")?,
+            commission_rate: row.try_get("commission_rate")?,
+            max_commission_rate_change: row.try_get("max_commission_rate_change")?,
+            email: row.try_get("email")?,
+            description: row.try_get("description")?,
+            website: row.try_get("website")?,
+            discord_handle: row.try_get("discord_handle")?,
+            avatar: row.try_get("avatar")?,
+        })
+    }
+}
+
+/// A validator with the history of its state, commission rate, metadata
+/// and consensus key, oldest first.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ValidatorDetail {
+    #[serde(flatten)]
+    pub validator: ValidatorInfo,
+    pub states: Vec<StateChange>,
+    pub commissions: Vec<CommissionChange>,
+    pub metadata: Vec<MetadataChange>,
+    pub consensus_keys: Vec<ConsensusKeyChange>,
+    pub tm_addresses: Vec<TmAddressRange>,
+}
+
+/// A change of state of a validator.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct StateChange {
+    pub height: i32,
+    pub epoch: Option<i64>,
+    #[serde(with = "hex::serde")]
+    pub tx_hash: Vec<u8>,
+    /// The transaction type without its `tx_` prefix, e.g `unjail_validator`.
+    pub action: String,
+}
+
+impl TryFrom<&Row> for StateChange {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(Self {
+            height: row.try_get("height")?,
+            epoch: row.try_get("epoch")?,
+            tx_hash: row.try_get("tx_hash")?,
+            action: row.try_get("action")?,
+        })
+    }
+}
+
+/// A commission rate set by a validator.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct CommissionChange {
+    pub height: i32,
+    pub epoch: Option<i64>,
+    #[serde(with = "hex::serde")]
+    pub tx_hash: Vec<u8>,
+    pub commission_rate: String,
+    /// Only set when becoming a validator.
+    pub max_commission_rate_change: Option<String>,
+}
+
+impl TryFrom<&Row> for CommissionChange {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(Self {
+            height: row.try_get("height")?,
+            epoch: row.try_get("epoch")?,
+            tx_hash: row.try_get("tx_hash")?,
+            commission_rate: row.try_get("commission_rate")?,
+            max_commission_rate_change: row.try_get("max_commission_rate_change")?,
+        })
+    }
+}
+
+/// The metadata set by a validator, empty fields are left unchanged.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct MetadataChange {
+    pub height: i32,
+    pub epoch: Option<i64>,
+    #[serde(with = "hex::serde")]
+    pub tx_hash: Vec<u8>,
+    pub email: Option<String>,
+    pub description: Option<String>,
+    pub website: Option<String>,
+    pub discord_handle: Option<String>,
+    pub avatar: Option<String>,
+}
+
+impl TryFrom<&Row> for MetadataChange {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(Self {
+            height: row.try_get("height")?,
+            epoch: row.try_get("epoch")?,
+            tx_hash: row.try_get("tx_hash")?,
+            email: row.try_get("email")?,
+            description: row.try_get("description")?,
+            website: row.try_get("website")?,
+            discord_handle: row.try_get("discord_handle")?,
+            avatar: row.try_get("avatar")?,
+        })
+    }
+}
+
+/// A consensus key used by a validator.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ConsensusKeyChange {
+    pub height: i32,
+    pub epoch: Option<i64>,
+    #[serde(with = "hex::serde")]
+    pub tx_hash: Vec<u8>,
+    pub consensus_key: String,
+}
+
+impl TryFrom<&Row> for ConsensusKeyChange {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(Self {
+            height: row.try_get("height")?,
+            epoch: row.try_get("epoch")?,
+            tx_hash: row.try_get("tx_hash")?,
+            consensus_key: row.try_get("consensus_key")?,
+        })
+    }
+}
+
+/// The Tendermint address of a consensus key of a validator and the range
+/// of heights it has been set over, `end_height` being none for the current
+/// key.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct TmAddressRange {
+    pub tm_address: String,
+    pub start_height: i32,
+    pub end_height: Option<i32>,
+}
+
+impl TryFrom<&Row> for TmAddressRange {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(Self {
+            tm_address: row.try_get("tm_address")?,
+            start_height: row.try_get("start_height")?,
+            end_height: row.try_get("end_height")?,
+        })
+    }
+}
+
+/// The uptime of the validators over a range of heights.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ValidatorsUptime {
+    pub start_height: i32,
+    pub end_height: i32,
+    /// The number of blocks indexed in the range.
+    pub blocks: i64,
+    pub validators: Vec<UptimeInfo>,
+}
+
+/// The commits signed by a validator, with any of its consensus keys, over
+/// a range of heights.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct UptimeInfo {
+    /// The Tendermint address of the consensus key used at the end of the
+    /// range.
+    pub tm_address: String,
+    /// The Namada address of the validator, if known.
+    pub validator: Option<String>,
+    pub signed: i64,
+    pub expected: i64,
+    pub uptime: f64,
+}
+
+impl UptimeInfo {
+    /// Reads the signatures of a validator from `row`, `expected` being
+    /// the number of blocks of the range.
+    pub fn from_row(row: &Row, expected: i64) -> Result<Self, Error> {
+        let signed: i64 = row.try_get("signed")?;
+        let uptime = if expected > 0 {
+            signed as f64 / expected as f64
+        } else {
+            0.0
+        };
+
+        Ok(Self {
+            tm_address: row.try_get("tm_address")?,
+            validator: row.try_get("validator")?,
+            signed,
+            expected,
+            uptime,
+        })
+    }
+}
+
+/// A block proposed by a validator.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ProposedBlock {
+    #[serde(with = "hex::serde")]
+    pub block_id: Vec<u8>,
+    pub height: i32,
+    pub time: String,
+    pub tm_address: String,
+}
+
+impl TryFrom<&Row> for ProposedBlock {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(Self {
+            block_id: row.try_get("block_id")?,
+            height: row.try_get("header_height")?,
+            time: row.try_get("header_time")?,
+            tm_address: row.try_get("header_proposer_address")?,
+        })
+    }
+}
+
+/// The blocks proposed by the validators over a range of heights.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ProposersInfo {
+    pub start_height: i32,
+    pub end_height: i32,
+    /// The number of blocks indexed in the range.
+    pub blocks: i64,
+    pub proposers: Vec<ProposerInfo>,
+}
+
+/// The blocks proposed by a validator, with any of its consensus keys, over
+/// a range of heights.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ProposerInfo {
+    /// The latest Tendermint address the validator proposed with.
+    pub tm_address: String,
+    /// The Namada address of the validator, if known.
+    pub validator: Option<String>,
+    pub proposed: i64,
+    /// The ratio of the blocks of the range proposed.
+    pub share: f64,
+    /// The amount bonded to the validator at the end of the range, in NAM,
+    /// without the bonds made at genesis.
+    pub bonded: Option<String>,
+}
+
+impl ProposerInfo {
+    /// Reads the blocks proposed by a validator from `row`, `blocks` being
+    /// the number of blocks of the range.
+    pub fn from_row(row: &Row, blocks: i64) -> Result<Self, Error> {
+        let proposed: i64 = row.try_get("proposed")?;
+        let share = if blocks > 0 {
+            proposed as f64 / blocks as f64
+        } else {
+            0.0
+        };
+
+        Ok(Self {
+            tm_address: row.try_get("tm_address")?,
+            validator: row.try_get("validator")?,
+            proposed,
+            share,
+            bonded: row.try_get("bonded")?,
+        })
+    }
+}
+
+/// The signatures of a validator in the commits of a range of heights.
+///
+/// The bitmaps are hex encoded, bit `i` (most significant bit first) being
+/// the height `start_height + i`. A height is absent when it is set in
+/// none of them.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct SignaturesInfo {
+    pub start_height: i32,
+    pub end_height: i32,
+    pub signed: u32,
+    pub absent: u32,
+    pub nil: u32,
+    /// Heights whose commit is not indexed.
+    pub missing: u32,
+    pub signed_bitmap: String,
+    pub nil_bitmap: String,
+    pub missing_bitmap: String,
+    /// The runs of consecutive heights not signed (absent or nil), a missing
+    /// height ending a run.
+    pub missed_streaks: Vec<MissedStreak>,
+}
+
+/// Consecutive heights a validator has not signed.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct MissedStreak {
+    pub start_height: i32,
+    pub end_height: i32,
+    pub length: u32,
+}
+
+impl SignaturesInfo {
+    /// Builds the signatures of the heights `start_height` to `end_height`
+    /// from the `block_id_flag` of the heights whose commit is indexed, the
+    /// ones without a flag being absent and the others missing. Only the
+    /// streaks of at least `min_streak` heights are kept.
+    pub fn new(
+        start_height: i32,
+        end_height: i32,
+        flags: &[(i32, Option<i32>)],
+        min_streak: u32,
+    ) -> Self {
+        let len = (end_height - start_height + 1).max(0) as usize;
+
+        // `None` for the missing heights, `Some(None)` for the absent ones
+        let mut by_height = vec![None; len];
+        for (height, flag) in flags {
+            if (start_height..=end_height).contains(height) {
+                by_height[(height - start_height) as usize] = Some(*flag);
+            }
+        }
+
+        let mut info = Self {
+            start_height,
+            end_height,
+            signed: 0,
+            absent: 0,
+            nil: 0,
+            missing: 0,
+            signed_bitmap: String::new(),
+            nil_bitmap: String::new(),
+            missing_bitmap: String::new(),
+            missed_streaks: vec![],
+        };
+        let mut signed_bitmap = vec![0u8; len.div_ceil(8)];
+        let mut nil_bitmap = vec![0u8; len.div_ceil(8)];
+        let mut missing_bitmap = vec![0u8; len.div_ceil(8)];
+        let mut streak_start = None;
+
+        for (i, flag) in by_height.iter().enumerate() {
+            let height = start_height + i as i32;
+            let bit = 0x80 >> (i % 8);
+
+            match flag {
+                Some(Some(BLOCK_ID_FLAG_COMMIT)) => {
+                    info.signed += 1;
+                    signed_bitmap[i / 8] |= bit;
+                }
+                Some(Some(BLOCK_ID_FLAG_NIL)) => {
+                    info.nil += 1;
+                    nil_bitmap[i / 8] |= bit;
+                }
+                Some(_) => info.absent += 1,
+                None => {
+                    info.missing += 1;
+                    missing_bitmap[i / 8] |= bit;
+                }
+            }
+
+            match flag {
+                Some(Some(BLOCK_ID_FLAG_COMMIT)) | None => {
+                    if let Some(start) = streak_start.take() {
+                        info.push_streak(start, height - 1, min_streak);
+                    }
+                }
+                Some(_) => {
+                    streak_start.get_or_insert(height);
+                }
+            }
+        }
+
+        if let Some(start) = streak_start {
+            info.push_streak(start, end_height, min_streak);
+        }
+
+        info.signed_bitmap = hex::encode(signed_bitmap);
+        info.nil_bitmap = hex::encode(nil_bitmap);
+        info.missing_bitmap = hex::encode(missing_bitmap);
+
+        info
+    }
+
+    fn push_streak(&mut self, start_height: i32, end_height: i32, min_streak: u32) {
+        let length = (end_height - start_height + 1) as u32;
+
+        if length >= min_streak {
+            self.missed_streaks.push(MissedStreak {
+                start_height,
+                end_height,
+                length,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signatures_bitmaps_and_streaks() {
+        // height 12 has no signature, 13 and 14 are nil, the commits of
+        // 16 and 19 are not indexed
+        let flags = [
+            (10, Some(2)),
+            (11, Some(2)),
+            (12, None),
+            (13, Some(3)),
+            (14, Some(3)),
+            (15, Some(2)),
+            (17, Some(1)),
+            (18, None),
+        ];
+        let info = SignaturesInfo::new(10, 19, &flags, 2);
+
+        assert_eq!(info.signed, 3);
+        assert_eq!(info.nil, 2);
+        assert_eq!(info.absent, 3);
+        assert_eq!(info.missing, 2);
+        assert_eq!(info.signed_bitmap, "c400");
+        assert_eq!(info.nil_bitmap, "1800");
+        assert_eq!(info.missing_bitmap, "0240");
+        assert_eq!(
+            info.missed_streaks,
+            vec![
+                MissedStreak {
+                    start_height: 12,
+                    end_height: 14,
+                    length: 3,
+                },
+                MissedStreak {
+                    start_height: 17,
+                    end_height: 18,
+                    length: 2,
+                },
+            ]
+        );
+    }
+}
@@ -35,18 +35,65 @@ pub fn get_create_transactions_table_query(network: &str) -> String {
         hash BYTEA NOT NULL,
         block_id BYTEA NOT NULL,
         tx_type TEXT NOT NULL,
-        wrapper_id BYTEA,
         fee_amount_per_gas_unit TEXT,
         fee_token TEXT,
         gas_limit_multiplier BIGINT,
         code BYTEA,
         data JSON,
-        return_code INTEGER
+        return_code INTEGER,
+        gas_used BIGINT,
+        info TEXT,
+        log TEXT,
+        fee_paid NUMERIC,
+        epoch BIGINT,
+        fee_payer TEXT
     );",
         network
     )
 }
 
+/// Brings a `transactions` table created by an older version up to date,
+/// every step is a no-op once applied.
+pub fn get_migrate_transactions_table_query(network: &str) -> String {
+    format!(
+        "ALTER TABLE {}.transactions
+        DROP COLUMN IF EXISTS wrapper_id,
+        ADD COLUMN IF NOT EXISTS gas_used BIGINT,
+        ADD COLUMN IF NOT EXISTS info TEXT,
+        ADD COLUMN IF NOT EXISTS log TEXT,
+        ADD COLUMN IF NOT EXISTS fee_paid NUMERIC,
+        ADD COLUMN IF NOT EXISTS epoch BIGINT,
+        ADD COLUMN IF NOT EXISTS fee_payer TEXT;",
+        network
+    )
+}
+
+/// Fills the `tx_wrappers` table from the `wrapper_id` column of a
+/// `transactions` table created by an older version, if it still has it.
+/// The decrypted transactions already linked are left as they are.
+pub fn get_migrate_tx_wrappers_query(network: &str) -> String {
+    format!(
+        "DO $$
+    BEGIN
+        IF EXISTS (
+            SELECT 1 FROM information_schema.columns
+            WHERE table_schema = '{0}'
+            AND table_name = 'transactions'
+            AND column_name = 'wrapper_id'
+        ) THEN
+            INSERT INTO {0}.tx_wrappers (wrapper_hash, inner_hash, block_id)
+            SELECT t.wrapper_id, t.hash, w.block_id
+            FROM {0}.transactions t
+            JOIN {0}.transactions w ON w.hash = t.wrapper_id AND w.tx_type = 'Wrapper'
+            WHERE NOT EXISTS (
+                SELECT 1 FROM {0}.tx_wrappers x WHERE x.inner_hash = t.hash
+            );
+        END IF;
+    END $$;",
+        network
+    )
+}
+
 pub fn get_create_evidences_table_query(network: &str) -> String {
     format!(
         "CREATE TABLE IF NOT EXISTS {}.evidences (
@@ -73,3 +120,481 @@ pub fn get_create_commit_signatures_table_query(network: &str) -> String {
         network
     )
 }
+
+pub fn get_create_tx_checksums_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.tx_checksums (
+        code_hash TEXT NOT NULL,
+        tx_type TEXT NOT NULL,
+        start_height INTEGER NOT NULL,
+        end_height INTEGER,
+        PRIMARY KEY (code_hash, start_height)
+    );",
+        network
+    )
+}
+
+pub fn get_create_decode_failures_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.decode_failures (
+        hash BYTEA NOT NULL,
+        block_id BYTEA NOT NULL,
+        tx_type TEXT,
+        code BYTEA,
+        data BYTEA NOT NULL,
+        error TEXT NOT NULL
+    );",
+        network
+    )
+}
+
+pub fn get_create_events_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.events (
+        block_id BYTEA NOT NULL,
+        height INTEGER NOT NULL,
+        source TEXT NOT NULL,
+        event_index INTEGER NOT NULL,
+        tx_index INTEGER,
+        tx_hash BYTEA,
+        event_type TEXT NOT NULL,
+        attributes JSONB NOT NULL
+    );",
+        network
+    )
+}
+
+pub fn get_create_tx_raw_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.tx_raw (
+        hash BYTEA NOT NULL,
+        block_id BYTEA NOT NULL,
+        tx_index INTEGER NOT NULL,
+        raw BYTEA NOT NULL
+    );",
+        network
+    )
+}
+
+pub fn get_create_ibc_transfers_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.ibc_transfers (
+        block_id BYTEA NOT NULL,
+        height INTEGER NOT NULL,
+        tx_hash BYTEA NOT NULL,
+        direction TEXT NOT NULL,
+        sender TEXT NOT NULL,
+        receiver TEXT NOT NULL,
+        denom TEXT NOT NULL,
+        amount NUMERIC NOT NULL,
+        port TEXT NOT NULL,
+        channel TEXT NOT NULL,
+        sequence BIGINT
+    );",
+        network
+    )
+}
+
+pub fn get_create_tx_wrappers_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.tx_wrappers (
+        wrapper_hash BYTEA NOT NULL,
+        inner_hash BYTEA NOT NULL,
+        block_id BYTEA NOT NULL
+    );",
+        network
+    )
+}
+
+pub fn get_create_transfers_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.transfers (
+        block_id BYTEA NOT NULL,
+        height INTEGER NOT NULL,
+        epoch BIGINT,
+        tx_hash BYTEA NOT NULL,
+        source TEXT NOT NULL,
+        target TEXT NOT NULL,
+        token TEXT NOT NULL,
+        amount NUMERIC NOT NULL,
+        key TEXT,
+        shielded BYTEA
+    );",
+        network
+    )
+}
+
+pub fn get_create_bonds_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.bonds (
+        block_id BYTEA NOT NULL,
+        height INTEGER NOT NULL,
+        epoch BIGINT,
+        tx_hash BYTEA NOT NULL,
+        validator TEXT NOT NULL,
+        source TEXT,
+        amount NUMERIC NOT NULL
+    );",
+        network
+    )
+}
+
+pub fn get_create_unbonds_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.unbonds (
+        block_id BYTEA NOT NULL,
+        height INTEGER NOT NULL,
+        epoch BIGINT,
+        tx_hash BYTEA NOT NULL,
+        validator TEXT NOT NULL,
+        source TEXT,
+        amount NUMERIC NOT NULL,
+        withdrawable_epoch BIGINT
+    );",
+        network
+    )
+}
+
+/// Adds the columns missing from an `unbonds` table created by an older
+/// version, every step is a no-op once applied.
+pub fn get_migrate_unbonds_table_query(network: &str) -> String {
+    format!(
+        "ALTER TABLE {}.unbonds
+        ADD COLUMN IF NOT EXISTS withdrawable_epoch BIGINT;",
+        network
+    )
+}
+
+pub fn get_create_redelegations_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.redelegations (
+        block_id BYTEA NOT NULL,
+        height INTEGER NOT NULL,
+        epoch BIGINT,
+        tx_hash BYTEA NOT NULL,
+        src_validator TEXT NOT NULL,
+        dest_validator TEXT NOT NULL,
+        owner TEXT NOT NULL,
+        amount NUMERIC NOT NULL
+    );",
+        network
+    )
+}
+
+pub fn get_create_withdrawals_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.withdrawals (
+        block_id BYTEA NOT NULL,
+        height INTEGER NOT NULL,
+        epoch BIGINT,
+        tx_hash BYTEA NOT NULL,
+        validator TEXT NOT NULL,
+        source TEXT
+    );",
+        network
+    )
+}
+
+pub fn get_create_reward_claims_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.reward_claims (
+        block_id BYTEA NOT NULL,
+        height INTEGER NOT NULL,
+        epoch BIGINT,
+        tx_hash BYTEA NOT NULL,
+        validator TEXT NOT NULL,
+        source TEXT
+    );",
+        network
+    )
+}
+
+pub fn get_create_votes_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.votes (
+        block_id BYTEA NOT NULL,
+        height INTEGER NOT NULL,
+        epoch BIGINT,
+        tx_hash BYTEA NOT NULL,
+        proposal_id BIGINT NOT NULL,
+        vote TEXT NOT NULL,
+        voter TEXT NOT NULL
+    );",
+        network
+    )
+}
+
+pub fn get_create_balance_changes_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.balance_changes (
+        block_id BYTEA NOT NULL,
+        height INTEGER NOT NULL,
+        tx_hash BYTEA NOT NULL,
+        address TEXT NOT NULL,
+        token TEXT NOT NULL,
+        amount NUMERIC NOT NULL,
+        kind TEXT NOT NULL
+    );",
+        network
+    )
+}
+
+pub fn get_create_bond_changes_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.bond_changes (
+        block_id BYTEA NOT NULL,
+        height INTEGER NOT NULL,
+        epoch BIGINT,
+        tx_hash BYTEA NOT NULL,
+        delegator TEXT NOT NULL,
+        validator TEXT NOT NULL,
+        amount NUMERIC NOT NULL,
+        kind TEXT NOT NULL
+    );",
+        network
+    )
+}
+
+pub fn get_create_unbond_withdrawals_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.unbond_withdrawals (
+        block_id BYTEA NOT NULL,
+        height INTEGER NOT NULL,
+        tx_hash BYTEA NOT NULL,
+        unbond_block_id BYTEA NOT NULL,
+        unbond_tx_hash BYTEA NOT NULL,
+        delegator TEXT NOT NULL,
+        validator TEXT NOT NULL,
+        amount NUMERIC NOT NULL
+    );",
+        network
+    )
+}
+
+pub fn get_create_proposals_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.proposals (
+        block_id BYTEA NOT NULL,
+        height INTEGER NOT NULL,
+        epoch BIGINT,
+        tx_hash BYTEA NOT NULL,
+        proposal_id BIGINT NOT NULL,
+        author TEXT NOT NULL,
+        proposal_type TEXT NOT NULL,
+        type_data JSON,
+        voting_start_epoch BIGINT NOT NULL,
+        voting_end_epoch BIGINT NOT NULL,
+        grace_epoch BIGINT NOT NULL,
+        content JSON
+    );",
+        network
+    )
+}
+
+pub fn get_create_validator_states_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.validator_states (
+        block_id BYTEA NOT NULL,
+        height INTEGER NOT NULL,
+        epoch BIGINT,
+        tx_hash BYTEA NOT NULL,
+        validator TEXT NOT NULL,
+        action TEXT NOT NULL
+    );",
+        network
+    )
+}
+
+pub fn get_create_validator_commissions_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.validator_commissions (
+        block_id BYTEA NOT NULL,
+        height INTEGER NOT NULL,
+        epoch BIGINT,
+        tx_hash BYTEA NOT NULL,
+        validator TEXT NOT NULL,
+        commission_rate NUMERIC NOT NULL,
+        max_commission_rate_change NUMERIC
+    );",
+        network
+    )
+}
+
+pub fn get_create_validator_metadata_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.validator_metadata (
+        block_id BYTEA NOT NULL,
+        height INTEGER NOT NULL,
+        epoch BIGINT,
+        tx_hash BYTEA NOT NULL,
+        validator TEXT NOT NULL,
+        email TEXT,
+        description TEXT,
+        website TEXT,
+        discord_handle TEXT,
+        avatar TEXT
+    );",
+        network
+    )
+}
+
+pub fn get_create_validator_consensus_keys_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.validator_consensus_keys (
+        block_id BYTEA NOT NULL,
+        height INTEGER NOT NULL,
+        epoch BIGINT,
+        tx_hash BYTEA NOT NULL,
+        validator TEXT NOT NULL,
+        consensus_key TEXT NOT NULL,
+        tm_address TEXT NOT NULL
+    );",
+        network
+    )
+}
+
+pub fn get_create_genesis_validators_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.genesis_validators (
+        address TEXT NOT NULL,
+        consensus_key TEXT NOT NULL,
+        tm_address TEXT NOT NULL
+    );",
+        network
+    )
+}
+
+/// The proof of stake parameters of the network, in epochs, a single row
+/// saved by the indexer from its configuration.
+pub fn get_create_pos_parameters_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.pos_parameters (
+        pipeline_len BIGINT NOT NULL,
+        unbonding_len BIGINT NOT NULL,
+        cubic_slashing_window_len BIGINT NOT NULL
+    );",
+        network
+    )
+}
+
+/// Drops the `validator_tm_addresses` view the table replaces, left by
+/// the versions before it.
+pub fn get_drop_validator_tm_addresses_view_query(network: &str) -> String {
+    format!(
+        "DO $$
+    BEGIN
+        IF EXISTS (
+            SELECT 1 FROM information_schema.views
+            WHERE table_schema = '{0}'
+            AND table_name = 'validator_tm_addresses'
+        ) THEN
+            DROP VIEW {0}.validator_tm_addresses;
+        END IF;
+    END $$;",
+        network
+    )
+}
+
+/// The Tendermint address of each consensus key of the validators with
+/// the range of heights it has been used over, `end_height` being null for
+/// the current key. The rows are computed while indexing by
+/// [get_refresh_validator_tm_addresses_query].
+pub fn get_create_validator_tm_addresses_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.validator_tm_addresses (
+        validator TEXT NOT NULL,
+        tm_address TEXT NOT NULL,
+        pipeline_epoch BIGINT,
+        start_height INTEGER,
+        end_height INTEGER
+    );",
+        network
+    )
+}
+
+/// Computes the rows of the `validator_tm_addresses` table again.
+///
+/// A key is used from its pipeline epoch, the first height of which is the
+/// first block with a wrapper made for this epoch or a later one. Keys whose
+/// pipeline epoch is not reached yet have no start height, keys without an
+/// epoch are used from the height of their transaction.
+pub fn get_refresh_validator_tm_addresses_query(network: &str) -> String {
+    format!(
+        "INSERT INTO {0}.validator_tm_addresses(
+            validator,
+            tm_address,
+            pipeline_epoch,
+            start_height,
+            end_height
+        )
+        WITH k AS (
+            SELECT address AS validator, tm_address, NULL::BIGINT AS pipeline_epoch, 0 AS height
+            FROM {0}.genesis_validators
+            UNION ALL
+            SELECT c.validator, c.tm_address, c.epoch + p.pipeline_len AS pipeline_epoch,
+                CASE WHEN c.epoch IS NULL THEN c.height ELSE (
+                    SELECT MIN(b.header_height)
+                    FROM {0}.transactions t
+                    JOIN {0}.blocks b ON b.block_id = t.block_id
+                    WHERE t.tx_type = 'Wrapper'
+                    AND t.epoch >= c.epoch + p.pipeline_len
+                ) END AS height
+            FROM {0}.validator_consensus_keys c
+            LEFT JOIN {0}.pos_parameters p ON TRUE
+        )
+        SELECT validator, tm_address, pipeline_epoch, height,
+            LEAD(height) OVER (PARTITION BY validator ORDER BY height) - 1
+        FROM k;",
+        network
+    )
+}
+
+/// The current state of the validators, from the latest rows of their
+/// history tables. The validators created at genesis are registered at
+/// height 0 with their genesis consensus key.
+pub fn get_create_validators_view_query(network: &str) -> String {
+    format!(
+        "CREATE OR REPLACE VIEW {0}.validators AS
+        WITH r AS (
+            SELECT validator, height
+            FROM {0}.validator_states
+            WHERE action = 'become_validator'
+            UNION ALL
+            SELECT address AS validator, 0 AS height
+            FROM {0}.genesis_validators
+        )
+        SELECT r.validator AS address,
+            r.height AS registered_height,
+            COALESCE((SELECT CASE s.action WHEN 'deactivate_validator' THEN 'inactive' ELSE 'active' END
+                FROM {0}.validator_states s WHERE s.validator = r.validator
+                ORDER BY s.height DESC LIMIT 1), 'active') AS state,
+            COALESCE((SELECT k.consensus_key FROM {0}.validator_consensus_keys k
+                WHERE k.validator = r.validator
+                ORDER BY k.height DESC LIMIT 1),
+                (SELECT g.consensus_key FROM {0}.genesis_validators g
+                WHERE g.address = r.validator LIMIT 1)) AS consensus_key,
+            (SELECT c.commission_rate FROM {0}.validator_commissions c
+                WHERE c.validator = r.validator
+                ORDER BY c.height DESC LIMIT 1) AS commission_rate,
+            (SELECT c.max_commission_rate_change FROM {0}.validator_commissions c
+                WHERE c.validator = r.validator AND c.max_commission_rate_change IS NOT NULL
+                ORDER BY c.height DESC LIMIT 1) AS max_commission_rate_change,
+            (SELECT m.email FROM {0}.validator_metadata m
+                WHERE m.validator = r.validator AND m.email IS NOT NULL
+                ORDER BY m.height DESC LIMIT 1) AS email,
+            (SELECT m.description FROM {0}.validator_metadata m
+                WHERE m.validator = r.validator AND m.description IS NOT NULL
+                ORDER BY m.height DESC LIMIT 1) AS description,
+            (SELECT m.website FROM {0}.validator_metadata m
+                WHERE m.validator = r.validator AND m.website IS NOT NULL
+                ORDER BY m.height DESC LIMIT 1) AS website,
+            (SELECT m.discord_handle FROM {0}.validator_metadata m
+                WHERE m.validator = r.validator AND m.discord_handle IS NOT NULL
+                ORDER BY m.height DESC LIMIT 1) AS discord_handle,
+            (SELECT m.avatar FROM {0}.validator_metadata m
+                WHERE m.validator = r.validator AND m.avatar IS NOT NULL
+                ORDER BY m.height DESC LIMIT 1) AS avatar
+        FROM r;",
+        network
+    )
+}
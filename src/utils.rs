@@ -1,5 +1,9 @@
+use crate::checksums::{ChecksumRange, Checksums};
 use namada_sdk::tx::data::TxType;
-use std::collections::HashMap;
+use namada_sdk::types::{address::Address, key::common::PublicKey};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::str::FromStr;
 use std::{env, fs};
 
 const CHECKSUMS_FILE_PATH_ENV: &str = "CHECKSUMS_FILE_PATH";
@@ -15,34 +19,158 @@ pub fn tx_type_name(tx_type: &TxType) -> String {
     }
 }
 
-pub fn load_checksums() -> Result<HashMap<String, String>, crate::Error> {
-    let checksums_file_path = env::var(CHECKSUMS_FILE_PATH_ENV);
-    let checksums_remote_url = env::var(CHECKSUMS_REMOTE_URL_ENV);
+/// Returns an amount in the smallest unit of its token, `amount` being
+/// written with all the decimal places of its denomination like
+/// `DenominatedAmount::to_string_precise` does, e.g `1.500000` -> `1500000`.
+pub fn to_base_units(amount: &str) -> String {
+    let digits: String = amount.chars().filter(|c| *c != '.').collect();
+    let digits = digits.trim_start_matches('0');
 
-    let checksums = match (checksums_file_path, checksums_remote_url) {
-        (Ok(path), _) => fs::read_to_string(path)?,
-        (_, Ok(url)) => ureq::get(&url)
+    if digits.is_empty() {
+        "0".to_string()
+    } else {
+        digits.to_string()
+    }
+}
+
+/// Loads the checksums from the files listed in `CHECKSUMS_FILE_PATH` and
+/// the urls listed in `CHECKSUMS_REMOTE_URL`, both comma separated, or from
+/// `checksums.json` if none is set.
+///
+/// Sources use the `checksums.json` format of the Namada node, an entry can
+/// also restrict the code hash to a range of heights:
+/// `{"tx_transfer.wasm": {"name": "tx_transfer.<hash>.wasm", "start_height": 1, "end_height": 1000}}`
+pub fn load_checksums() -> Result<Checksums, crate::Error> {
+    let split = |v: String| -> Vec<String> {
+        v.split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(String::from)
+            .collect()
+    };
+
+    let paths = env::var(CHECKSUMS_FILE_PATH_ENV)
+        .map(split)
+        .unwrap_or_default();
+    let urls = env::var(CHECKSUMS_REMOTE_URL_ENV)
+        .map(split)
+        .unwrap_or_default();
+
+    let mut sources = Vec::new();
+    for path in paths.iter() {
+        sources.push(fs::read_to_string(path)?);
+    }
+    for url in urls.iter() {
+        let checksums = ureq::get(url)
             .call()
             .map_err(|e| crate::Error::Generic(Box::new(e)))?
-            .into_string()?,
-        _ => fs::read_to_string(CHECKSUMS_DEFAULT_PATH)?,
-    };
+            .into_string()?;
+        sources.push(checksums);
+    }
+    if paths.is_empty() && urls.is_empty() {
+        sources.push(fs::read_to_string(CHECKSUMS_DEFAULT_PATH)?);
+    }
+
+    let mut checksums = Checksums::default();
+    for source in sources.iter() {
+        parse_checksums(source, &mut checksums)?;
+    }
+
+    Ok(checksums)
+}
 
-    let json: serde_json::Value = serde_json::from_str(&checksums)?;
+/// Loads the consensus keys of the validators created at genesis from the
+/// JSON file `path`, mapping the address of each validator to its key:
+/// `{"tnam1q...": "tpknam1q..."}`.
+pub fn load_genesis_validators(path: &Path) -> Result<Vec<(Address, PublicKey)>, crate::Error> {
+    let source = fs::read_to_string(path)?;
+    let keys: BTreeMap<String, String> = serde_json::from_str(&source)?;
+
+    keys.iter()
+        .map(|(address, key)| {
+            let address = Address::from_str(address)
+                .map_err(|e| crate::Error::InvalidGenesisValidator(format!("{address}: {e}")))?;
+            let key = PublicKey::from_str(key)
+                .map_err(|e| crate::Error::InvalidGenesisValidator(format!("{key}: {e}")))?;
+
+            Ok((address, key))
+        })
+        .collect()
+}
+
+fn parse_checksums(source: &str, checksums: &mut Checksums) -> Result<(), crate::Error> {
+    let json: serde_json::Value = serde_json::from_str(source)?;
     let obj = json.as_object().ok_or(crate::Error::InvalidChecksum)?;
 
-    let mut checksums_map = HashMap::new();
-    for value in obj.iter() {
-        let hash = value
-            .1
-            .as_str()
-            .ok_or(crate::Error::InvalidChecksum)?
+    for (key, value) in obj.iter() {
+        let (name, start_height, end_height) = match value {
+            serde_json::Value::String(name) => (name.as_str(), 0, None),
+            serde_json::Value::Object(entry) => (
+                entry
+                    .get("name")
+                    .and_then(|n| n.as_str())
+                    .ok_or(crate::Error::InvalidChecksum)?,
+                entry
+                    .get("start_height")
+                    .and_then(|h| h.as_u64())
+                    .unwrap_or_default(),
+                entry.get("end_height").and_then(|h| h.as_u64()),
+            ),
+            _ => return Err(crate::Error::InvalidChecksum),
+        };
+
+        let hash = name
             .split('.')
-            .collect::<Vec<&str>>()[1];
-        let type_tx = value.0.split('.').collect::<Vec<&str>>()[0];
+            .nth(1)
+            .ok_or(crate::Error::InvalidChecksum)?;
+        let type_tx = key.split('.').collect::<Vec<&str>>()[0];
+
+        checksums.insert(
+            hash.to_string(),
+            ChecksumRange {
+                tx_type: type_tx.to_string(),
+                start_height,
+                end_height,
+            },
+        );
+    }
 
-        checksums_map.insert(hash.to_string(), type_tx.to_string());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_checksums_entries() {
+        let source = r#"{
+            "tx_transfer.wasm": "tx_transfer.1234abcd.wasm",
+            "tx_bond.wasm": {"name": "tx_bond.5678ef01.wasm", "start_height": 10, "end_height": 20}
+        }"#;
+
+        let mut checksums = Checksums::default();
+        parse_checksums(source, &mut checksums).unwrap();
+
+        assert_eq!(checksums.len(), 2);
+        assert_eq!(checksums.tx_type("1234abcd", 0), Some("tx_transfer"));
+        assert_eq!(checksums.tx_type("5678ef01", 15), Some("tx_bond"));
+        assert_eq!(checksums.tx_type("5678ef01", 21), None);
     }
 
-    Ok(checksums_map)
+    #[test]
+    fn parse_checksums_malformed() {
+        let sources = [
+            "not json",
+            r#"["tx_transfer.1234abcd.wasm"]"#,
+            r#"{"tx_transfer.wasm": "tx_transfer"}"#,
+            r#"{"tx_transfer.wasm": 1}"#,
+            r#"{"tx_bond.wasm": {"start_height": 10}}"#,
+        ];
+
+        for source in sources {
+            let mut checksums = Checksums::default();
+            assert!(parse_checksums(source, &mut checksums).is_err(), "{source}");
+        }
+    }
 }
@@ -3,6 +3,7 @@ mod utils;
 #[cfg(test)]
 mod save_block {
     use namadexer::utils::load_checksums;
+    use namadexer::DecoderRegistry;
     use std::fs;
     use tendermint::block::Block;
     use tendermint_rpc::endpoint::block_results;
@@ -19,6 +20,7 @@ mod save_block {
         let db = create_test_db(helper_db.pool(), TESTING_DB_NAME).await;
 
         let checksums_map = load_checksums().unwrap();
+        let decoders = DecoderRegistry::default();
 
         let data = fs::read_to_string("./tests/blocks_vector.json").unwrap();
         let blocks: Vec<Block> = serde_json::from_str(&data).unwrap();
@@ -28,7 +30,7 @@ mod save_block {
         db.create_tables().await.unwrap();
 
         for i in 0..blocks.len() {
-            db.save_block(&blocks[i], &block_results[i], &checksums_map)
+            db.save_block(&blocks[i], &block_results[i], &checksums_map, &decoders)
                 .await
                 .unwrap();
         }